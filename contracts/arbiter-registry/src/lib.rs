@@ -5,13 +5,23 @@
 extern crate alloc;
 
 use alloc::vec::Vec;
-use alloy_sol_types::{sol, SolError};
+use alloy_sol_types::{sol, SolCall, SolError};
 use stylus_sdk::{
-    alloy_primitives::{Address, U256},
-    evm,
+    alloy_primitives::{b256, Address, B256, U256},
     prelude::*,
 };
 
+// ============================================================================
+// Access Control
+// ============================================================================
+
+/// Held by `admin` alone; grants and revokes every other role.
+pub const DEFAULT_ADMIN_ROLE: B256 = B256::ZERO;
+
+/// keccak256("SLASHER_ROLE"): slashes misbehaving arbiters
+pub const SLASHER_ROLE: B256 =
+    b256!("12b42e8a160f6064dc959c6f251e3af0750ad213dbecf573b4710d67d6c28e39");
+
 // ============================================================================
 // Enums
 // ============================================================================
@@ -64,7 +74,7 @@ sol_storage! {
         /// Mapping of arbiter address to ArbiterProfile data
         mapping(address => ArbiterProfile) arbiters;
 
-        /// Admin address (can slash)
+        /// Admin address (manages roles and the escrow)
         address admin;
 
         /// Token used for staking
@@ -72,6 +82,21 @@ sol_storage! {
 
         /// Minimum stake required
         uint256 min_stake;
+
+        /// Escrow contract allowed to report dispute resolutions
+        address escrow;
+
+        /// Enumerable set of active arbiters
+        address[] active_arbiters;
+
+        /// Position of each active arbiter in `active_arbiters`, plus one (0 if absent)
+        mapping(address => uint256) active_index;
+
+        /// Role members per role ID; DEFAULT_ADMIN_ROLE is held by `admin` instead
+        mapping(bytes32 => mapping(address => bool)) roles;
+
+        /// Nominee awaiting `accept_admin` (Address::ZERO if none)
+        address pending_admin;
     }
 }
 
@@ -79,7 +104,7 @@ sol_storage! {
 // Interfaces
 // ============================================================================
 
-sol_interface! {
+sol! {
     interface IERC20 {
         function transferFrom(address sender, address recipient, uint256 amount) external returns (bool);
     }
@@ -101,6 +126,45 @@ sol! {
     /// Error thrown when token transfer fails
     #[derive(Debug)]
     error TokenTransferFailed();
+
+    /// Error thrown when caller is not the configured escrow contract
+    #[derive(Debug)]
+    error NotEscrow(address caller);
+
+    /// Error thrown when no eligible active arbiter is available for selection
+    #[derive(Debug)]
+    error NoActiveArbiters();
+
+    /// Error thrown when caller lacks the role an operation requires
+    #[derive(Debug)]
+    error MissingRole(bytes32 role, address account);
+
+    /// Error thrown when a role cannot be granted or revoked
+    #[derive(Debug)]
+    error InvalidRole(bytes32 role);
+
+    /// Error thrown when the zero address is set or nominated as admin
+    #[derive(Debug)]
+    error InvalidAdmin(address admin);
+
+    /// Error thrown when caller is not the nominated admin
+    #[derive(Debug)]
+    error NotPendingAdmin(address caller);
+
+    /// Error thrown when there is no admin nomination to cancel
+    #[derive(Debug)]
+    error NoPendingAdmin();
+
+    /// Error thrown when stake or counter arithmetic would overflow
+    #[derive(Debug)]
+    error ArithmeticOverflow();
+}
+
+/// Adds two amounts, reverting with `ArithmeticOverflow` instead of wrapping.
+#[inline]
+fn safe_add(a: U256, b: U256) -> Result<U256, Vec<u8>> {
+    a.checked_add(b)
+        .ok_or_else(|| ArithmeticOverflow {}.abi_encode())
 }
 
 // ============================================================================
@@ -109,15 +173,133 @@ sol! {
 
 #[public]
 impl ArbiterRegistry {
-    /// Initializes the arbiter registry with the specified staking token and minimum stake requirements.
-    pub fn initialize(&mut self, staking_token: Address, min_stake: U256) {
-        if self.admin.get() != Address::ZERO {
-            return;
+    /// Initializes the arbiter registry with its admin, staking token and minimum stake in the
+    /// deployment transaction, so the registry can never be claimed by another account.
+    #[constructor]
+    pub fn constructor(
+        &mut self,
+        admin: Address,
+        staking_token: Address,
+        min_stake: U256,
+    ) -> Result<(), Vec<u8>> {
+        if admin == Address::ZERO {
+            return Err(InvalidAdmin { admin }.abi_encode());
         }
-        let caller = self.vm().msg_sender();
-        self.admin.set(caller);
+
+        self.admin.set(admin);
         self.staking_token.set(staking_token);
         self.min_stake.set(min_stake);
+        self.set_role(SLASHER_ROLE, admin, true);
+
+        log(
+            self.vm(),
+            Initialized {
+                admin,
+                staking_token,
+                min_stake,
+            },
+        );
+        Ok(())
+    }
+
+    /// Nominates a new administrator, who takes over once they call `accept_admin`.
+    /// Administrators only.
+    pub fn propose_admin(&mut self, new_admin: Address) -> Result<(), Vec<u8>> {
+        let caller = self.vm().msg_sender();
+        let admin = self.admin.get();
+        if caller != admin {
+            return Err(NotAdmin { caller }.abi_encode());
+        }
+        if new_admin == Address::ZERO {
+            return Err(InvalidAdmin { admin: new_admin }.abi_encode());
+        }
+
+        self.pending_admin.set(new_admin);
+        log(
+            self.vm(),
+            AdminProposed {
+                admin,
+                pending_admin: new_admin,
+            },
+        );
+        Ok(())
+    }
+
+    /// Completes an admin transfer. Only callable by the nominated admin.
+    pub fn accept_admin(&mut self) -> Result<(), Vec<u8>> {
+        let caller = self.vm().msg_sender();
+        let pending = self.pending_admin.get();
+        if pending == Address::ZERO || caller != pending {
+            return Err(NotPendingAdmin { caller }.abi_encode());
+        }
+
+        let previous_admin = self.admin.get();
+        self.admin.set(pending);
+        self.pending_admin.set(Address::ZERO);
+
+        log(
+            self.vm(),
+            AdminTransferred {
+                previous_admin,
+                new_admin: pending,
+            },
+        );
+        Ok(())
+    }
+
+    /// Withdraws a pending admin nomination.
+    /// Administrators only.
+    pub fn cancel_admin_proposal(&mut self) -> Result<(), Vec<u8>> {
+        let caller = self.vm().msg_sender();
+        if caller != self.admin.get() {
+            return Err(NotAdmin { caller }.abi_encode());
+        }
+
+        let pending = self.pending_admin.get();
+        if pending == Address::ZERO {
+            return Err(NoPendingAdmin {}.abi_encode());
+        }
+
+        self.pending_admin.set(Address::ZERO);
+        log(
+            self.vm(),
+            AdminProposalCancelled {
+                pending_admin: pending,
+            },
+        );
+        Ok(())
+    }
+
+    /// Retrieves the current administrator.
+    pub fn get_admin(&self) -> Address {
+        self.admin.get()
+    }
+
+    /// Retrieves the nominee awaiting `accept_admin` (Address::ZERO if none).
+    pub fn get_pending_admin(&self) -> Address {
+        self.pending_admin.get()
+    }
+
+    /// Grants `role` to `account`. Administrators only.
+    pub fn grant_role(&mut self, role: B256, account: Address) -> Result<(), Vec<u8>> {
+        self.require_role_admin(role)?;
+        self.set_role(role, account, true);
+        Ok(())
+    }
+
+    /// Revokes `role` from `account`. Administrators only.
+    pub fn revoke_role(&mut self, role: B256, account: Address) -> Result<(), Vec<u8>> {
+        self.require_role_admin(role)?;
+        self.set_role(role, account, false);
+        Ok(())
+    }
+
+    /// Checks whether `account` holds `role`.
+    pub fn has_role(&self, role: B256, account: Address) -> bool {
+        if role == DEFAULT_ADMIN_ROLE {
+            return account == self.admin.get();
+        }
+        self.roles.getter(role).get(account)
     }
 
     /// Allows a user to register as an arbiter by staking the required amount of the designated utility token.
@@ -135,12 +317,18 @@ impl ArbiterRegistry {
         }
 
         // Transfer tokens
-        let token = IERC20::new(token_addr);
-        let contract_address = self.vm().contract_address();
-        let config = stylus_sdk::call::Call::new_in(self);
-        let result = token.transfer_from(config, caller, contract_address, amount);
+        let call = IERC20::transferFromCall {
+            sender: caller,
+            recipient: self.vm().contract_address(),
+            amount,
+        };
+        let data = self.vm().call(&self, token_addr, &call.abi_encode());
+        let transferred = data
+            .ok()
+            .and_then(|data| IERC20::transferFromCall::abi_decode_returns(&data, true).ok())
+            .is_some_and(|result| result._0);
 
-        if result.is_err() || !result.unwrap() {
+        if !transferred {
             return Err(TokenTransferFailed {}.abi_encode());
         }
 
@@ -148,24 +336,32 @@ impl ArbiterRegistry {
         let mut profile = self.arbiters.setter(caller);
         let current_stake = profile.stake.get();
 
-        if current_stake == U256::ZERO {
+        let is_new = current_stake == U256::ZERO;
+        if is_new {
             profile.reputation.set(U256::from(100));
             profile.disputes_resolved.set(U256::ZERO);
             profile.is_active.set(true);
         }
 
-        profile.stake.set(current_stake + amount);
+        profile.stake.set(safe_add(current_stake, amount)?);
 
-        evm::log(ArbiterRegistered {
-            arbiter: caller,
-            amount,
-        });
+        if is_new {
+            self.add_active(caller);
+        }
+
+        log(
+            self.vm(),
+            ArbiterRegistered {
+                arbiter: caller,
+                amount,
+            },
+        );
 
         Ok(())
     }
 
     /// Penalizes a registered arbiter by slashing their staked tokens and reducing their reputation score
-    /// Administrators only.
+    /// Holders of SLASHER_ROLE only.
     pub fn slash_arbiter(
         &mut self,
         arbiter: Address,
@@ -173,8 +369,12 @@ impl ArbiterRegistry {
         reason: u8,
     ) -> Result<(), Vec<u8>> {
         let caller = self.vm().msg_sender();
-        if caller != self.admin.get() {
-            return Err(NotAdmin { caller }.abi_encode());
+        if !self.has_role(SLASHER_ROLE, caller) {
+            return Err(MissingRole {
+                role: SLASHER_ROLE,
+                account: caller,
+            }
+            .abi_encode());
         }
 
         let mut profile = self.arbiters.setter(arbiter);
@@ -203,11 +403,107 @@ impl ArbiterRegistry {
         } else {
             profile.reputation.set(U256::ZERO);
             profile.is_active.set(false);
+            self.remove_active(arbiter);
+        }
+
+        Ok(())
+    }
+
+    /// Sets the escrow contract that is allowed to report dispute resolutions.
+    /// Administrators only.
+    pub fn set_escrow(&mut self, escrow: Address) -> Result<(), Vec<u8>> {
+        let caller = self.vm().msg_sender();
+        if caller != self.admin.get() {
+            return Err(NotAdmin { caller }.abi_encode());
+        }
+
+        self.escrow.set(escrow);
+        Ok(())
+    }
+
+    /// Records that the arbiter resolved a dispute on the given deal, updating their track record.
+    /// Only callable by the configured escrow contract.
+    pub fn record_resolution(
+        &mut self,
+        arbiter: Address,
+        deal_id: U256,
+        amount: U256,
+    ) -> Result<(), Vec<u8>> {
+        let caller = self.vm().msg_sender();
+        if caller == Address::ZERO || caller != self.escrow.get() {
+            return Err(NotEscrow { caller }.abi_encode());
         }
 
+        let mut profile = self.arbiters.setter(arbiter);
+        let resolved = profile.disputes_resolved.get();
+        profile.disputes_resolved.set(safe_add(resolved, U256::from(1))?);
+
+        log(
+            self.vm(),
+            ResolutionRecorded {
+                arbiter,
+                deal_id,
+                amount,
+            },
+        );
+
         Ok(())
     }
 
+    /// Retrieves the number of disputes the specified arbiter has resolved.
+    pub fn get_disputes_resolved(&self, arbiter: Address) -> U256 {
+        self.arbiters.get(arbiter).disputes_resolved.get()
+    }
+
+    /// Retrieves the escrow contract allowed to report dispute resolutions.
+    pub fn get_escrow(&self) -> Address {
+        self.escrow.get()
+    }
+
+    /// Retrieves every currently active arbiter.
+    pub fn get_active_arbiters(&self) -> Vec<Address> {
+        let mut arbiters = Vec::new();
+        for i in 0..self.active_arbiters.len() {
+            if let Some(arbiter) = self.active_arbiters.get(i) {
+                arbiters.push(arbiter);
+            }
+        }
+        arbiters
+    }
+
+    /// Selects an active arbiter with probability proportional to stake, using `seed` as the
+    /// source of randomness. Addresses in `exclude` (e.g. the deal parties) are never selected.
+    pub fn select_arbiter(&self, seed: U256, exclude: Vec<Address>) -> Result<Address, Vec<u8>> {
+        let mut candidates = Vec::new();
+        let mut total_stake = U256::ZERO;
+        for i in 0..self.active_arbiters.len() {
+            let arbiter = self.active_arbiters.get(i).unwrap_or(Address::ZERO);
+            if exclude.contains(&arbiter) {
+                continue;
+            }
+            let stake = self.arbiters.get(arbiter).stake.get();
+            if stake == U256::ZERO {
+                continue;
+            }
+            total_stake = safe_add(total_stake, stake)?;
+            candidates.push((arbiter, stake));
+        }
+
+        if candidates.is_empty() {
+            return Err(NoActiveArbiters {}.abi_encode());
+        }
+
+        let mut target = seed % total_stake;
+        for (arbiter, stake) in candidates {
+            if target < stake {
+                return Ok(arbiter);
+            }
+            target -= stake;
+        }
+
+        Err(NoActiveArbiters {}.abi_encode())
+    }
+
     /// Retrieves the active status, total staked amount, and reputation score of the specified arbiter.
     pub fn get_arbiter_status(&self, arbiter: Address) -> (bool, U256, U256) {
         let profile = self.arbiters.get(arbiter);
@@ -219,10 +515,92 @@ impl ArbiterRegistry {
     }
 }
 
+// ============================================================================
+// Helper Functions (Internal)
+// ============================================================================
+
+impl ArbiterRegistry {
+    /// Checks that the caller may grant or revoke `role`.
+    fn require_role_admin(&self, role: B256) -> Result<(), Vec<u8>> {
+        let caller = self.vm().msg_sender();
+        if caller != self.admin.get() {
+            return Err(NotAdmin { caller }.abi_encode());
+        }
+        if role == DEFAULT_ADMIN_ROLE {
+            return Err(InvalidRole { role }.abi_encode());
+        }
+        Ok(())
+    }
+
+    /// Records a role change, emitting an event only when membership actually changes.
+    fn set_role(&mut self, role: B256, account: Address, granted: bool) {
+        if self.roles.getter(role).get(account) == granted {
+            return;
+        }
+        self.roles.setter(role).setter(account).set(granted);
+
+        let sender = self.vm().msg_sender();
+        if granted {
+            log(
+                self.vm(),
+                RoleGranted {
+                    role,
+                    account,
+                    sender,
+                },
+            );
+        } else {
+            log(
+                self.vm(),
+                RoleRevoked {
+                    role,
+                    account,
+                    sender,
+                },
+            );
+        }
+    }
+
+    /// Adds an arbiter to the active set if not already present.
+    fn add_active(&mut self, arbiter: Address) {
+        if self.active_index.get(arbiter) != U256::ZERO {
+            return;
+        }
+        self.active_arbiters.push(arbiter);
+        let len = U256::from(self.active_arbiters.len());
+        self.active_index.setter(arbiter).set(len);
+    }
+
+    /// Removes an arbiter from the active set by swapping in the last entry.
+    fn remove_active(&mut self, arbiter: Address) {
+        let position = self.active_index.get(arbiter);
+        if position == U256::ZERO {
+            return;
+        }
+
+        let index = position.to::<usize>() - 1;
+        let last_index = self.active_arbiters.len() - 1;
+        if index != last_index {
+            let last = self.active_arbiters.get(last_index).unwrap();
+            self.active_arbiters.setter(index).unwrap().set(last);
+            self.active_index.setter(last).set(position);
+        }
+        self.active_arbiters.pop();
+        self.active_index.setter(arbiter).set(U256::ZERO);
+    }
+}
+
 // ============================================================================
 // Events
 // ============================================================================
 
 sol! {
+    event Initialized(address indexed admin, address staking_token, uint256 min_stake);
     event ArbiterRegistered(address indexed arbiter, uint256 amount);
+    event ResolutionRecorded(address indexed arbiter, uint256 indexed deal_id, uint256 amount);
+    event RoleGranted(bytes32 indexed role, address indexed account, address indexed sender);
+    event RoleRevoked(bytes32 indexed role, address indexed account, address indexed sender);
+    event AdminProposed(address indexed admin, address indexed pending_admin);
+    event AdminTransferred(address indexed previous_admin, address indexed new_admin);
+    event AdminProposalCancelled(address indexed pending_admin);
 }
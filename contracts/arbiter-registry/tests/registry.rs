@@ -0,0 +1,492 @@
+//! `ArbiterRegistry` unit tests on the stylus-test `TestVM`, with the staking token mocked at
+//! the call level.
+
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::{sol, SolCall, SolError, SolEvent, SolValue};
+use arbiter_registry::{
+    AdminTransferred, ArbiterRegistered, ArbiterRegistry, Initialized, InvalidAdmin, InvalidRole,
+    LowStake, MissingRole, NoActiveArbiters, NoPendingAdmin, NotAdmin, NotEscrow, NotPendingAdmin,
+    ResolutionRecorded, RoleGranted, TokenTransferFailed, DEFAULT_ADMIN_ROLE, SLASHER_ROLE,
+};
+use stylus_sdk::testing::*;
+
+const REGISTRY: Address = Address::repeat_byte(0x4E);
+const ADMIN: Address = Address::repeat_byte(0xAD);
+const ESCROW: Address = Address::repeat_byte(0xE5);
+const TOKEN: Address = Address::repeat_byte(0x70);
+const STRANGER: Address = Address::repeat_byte(0x57);
+const ARBITER: Address = Address::repeat_byte(0xA1);
+const ARBITER_2: Address = Address::repeat_byte(0xA2);
+const ARBITER_3: Address = Address::repeat_byte(0xA3);
+
+const MIN_STAKE: u64 = 1_000;
+
+sol! {
+    interface IERC20 {
+        function transferFrom(address sender, address recipient, uint256 amount) external returns (bool);
+    }
+}
+
+fn u(value: u64) -> U256 {
+    U256::from(value)
+}
+
+/// Registry deployed at `REGISTRY` with `ADMIN` as admin and `TOKEN` as staking token.
+fn setup() -> (TestVM, ArbiterRegistry) {
+    let vm = TestVM::default();
+    vm.set_contract_address(REGISTRY);
+    let mut registry = ArbiterRegistry::from(&vm);
+    vm.set_sender(ADMIN);
+    registry
+        .constructor(ADMIN, TOKEN, u(MIN_STAKE))
+        .expect("constructor");
+    (vm, registry)
+}
+
+fn mock_stake(vm: &TestVM, arbiter: Address, amount: U256, accepted: bool) {
+    let call = IERC20::transferFromCall {
+        sender: arbiter,
+        recipient: REGISTRY,
+        amount,
+    };
+    vm.mock_call(TOKEN, call.abi_encode(), Ok(accepted.abi_encode()));
+}
+
+fn register(vm: &TestVM, registry: &mut ArbiterRegistry, arbiter: Address, amount: u64) {
+    mock_stake(vm, arbiter, u(amount), true);
+    vm.set_sender(arbiter);
+    registry.register_as_arbiter(u(amount)).expect("register");
+}
+
+fn events<E: SolEvent>(vm: &TestVM) -> Vec<E> {
+    vm.get_emitted_logs()
+        .into_iter()
+        .filter(|(topics, _)| topics.first() == Some(&E::SIGNATURE_HASH))
+        .map(|(topics, data)| E::decode_raw_log(topics, &data, true).expect("decode"))
+        .collect()
+}
+
+// ============================================================================
+// Initialization
+// ============================================================================
+
+#[test]
+fn constructor_sets_admin_and_emits_initialized() {
+    let (vm, mut registry) = setup();
+    assert_eq!(registry.get_admin(), ADMIN);
+
+    let initialized = events::<Initialized>(&vm);
+    assert_eq!(
+        (
+            initialized[0].admin,
+            initialized[0].staking_token,
+            initialized[0].min_stake
+        ),
+        (ADMIN, TOKEN, u(MIN_STAKE))
+    );
+
+    vm.set_sender(STRANGER);
+    assert_eq!(
+        registry.set_escrow(ESCROW),
+        Err(NotAdmin { caller: STRANGER }.abi_encode())
+    );
+    vm.set_sender(ADMIN);
+    assert!(registry.set_escrow(ESCROW).is_ok());
+}
+
+#[test]
+fn constructor_rejects_zero_admin() {
+    let vm = TestVM::default();
+    let mut registry = ArbiterRegistry::from(&vm);
+    assert_eq!(
+        registry.constructor(Address::ZERO, TOKEN, u(MIN_STAKE)),
+        Err(InvalidAdmin {
+            admin: Address::ZERO
+        }
+        .abi_encode())
+    );
+}
+
+#[test]
+fn admin_transfer_takes_effect_on_acceptance() {
+    let (vm, mut registry) = setup();
+
+    vm.set_sender(ADMIN);
+    registry.propose_admin(STRANGER).unwrap();
+    assert_eq!(registry.get_pending_admin(), STRANGER);
+    // The current admin stays in control until the nominee accepts
+    assert!(registry.set_escrow(ESCROW).is_ok());
+
+    vm.set_sender(ESCROW);
+    assert_eq!(
+        registry.accept_admin(),
+        Err(NotPendingAdmin { caller: ESCROW }.abi_encode())
+    );
+
+    vm.set_sender(STRANGER);
+    registry.accept_admin().unwrap();
+    assert_eq!(registry.get_admin(), STRANGER);
+    assert_eq!(registry.get_pending_admin(), Address::ZERO);
+    assert!(registry.set_escrow(ESCROW).is_ok());
+
+    let transferred = events::<AdminTransferred>(&vm);
+    assert_eq!(
+        (transferred[0].previous_admin, transferred[0].new_admin),
+        (ADMIN, STRANGER)
+    );
+
+    vm.set_sender(ADMIN);
+    assert_eq!(
+        registry.set_escrow(ESCROW),
+        Err(NotAdmin { caller: ADMIN }.abi_encode())
+    );
+}
+
+#[test]
+fn admin_nomination_can_be_cancelled() {
+    let (vm, mut registry) = setup();
+
+    vm.set_sender(STRANGER);
+    assert_eq!(
+        registry.propose_admin(STRANGER),
+        Err(NotAdmin { caller: STRANGER }.abi_encode())
+    );
+
+    vm.set_sender(ADMIN);
+    registry.propose_admin(STRANGER).unwrap();
+    registry.cancel_admin_proposal().unwrap();
+    assert_eq!(
+        registry.cancel_admin_proposal(),
+        Err(NoPendingAdmin {}.abi_encode())
+    );
+
+    vm.set_sender(STRANGER);
+    assert_eq!(
+        registry.accept_admin(),
+        Err(NotPendingAdmin { caller: STRANGER }.abi_encode())
+    );
+    assert_eq!(registry.get_admin(), ADMIN);
+}
+
+// ============================================================================
+// Registration
+// ============================================================================
+
+#[test]
+fn register_stakes_and_activates() {
+    let (vm, mut registry) = setup();
+    register(&vm, &mut registry, ARBITER, MIN_STAKE);
+
+    assert_eq!(
+        registry.get_arbiter_status(ARBITER),
+        (true, u(MIN_STAKE), u(100))
+    );
+    assert_eq!(registry.get_active_arbiters(), vec![ARBITER]);
+
+    let registered = events::<ArbiterRegistered>(&vm);
+    assert_eq!(registered.len(), 1);
+    assert_eq!(registered[0].arbiter, ARBITER);
+    assert_eq!(registered[0].amount, u(MIN_STAKE));
+}
+
+#[test]
+fn register_again_tops_up_stake() {
+    let (vm, mut registry) = setup();
+    register(&vm, &mut registry, ARBITER, MIN_STAKE);
+    register(&vm, &mut registry, ARBITER, 2 * MIN_STAKE);
+
+    assert_eq!(
+        registry.get_arbiter_status(ARBITER),
+        (true, u(3 * MIN_STAKE), u(100))
+    );
+    assert_eq!(registry.get_active_arbiters(), vec![ARBITER]);
+}
+
+#[test]
+fn register_rejects_low_stake() {
+    let (vm, mut registry) = setup();
+    vm.set_sender(ARBITER);
+
+    assert_eq!(
+        registry.register_as_arbiter(u(MIN_STAKE - 1)),
+        Err(LowStake {
+            provided: u(MIN_STAKE - 1),
+            required: u(MIN_STAKE),
+        }
+        .abi_encode())
+    );
+    assert!(registry.get_active_arbiters().is_empty());
+}
+
+#[test]
+fn register_fails_when_token_transfer_fails() {
+    let (vm, mut registry) = setup();
+    mock_stake(&vm, ARBITER, u(MIN_STAKE), false);
+    vm.set_sender(ARBITER);
+
+    assert_eq!(
+        registry.register_as_arbiter(u(MIN_STAKE)),
+        Err(TokenTransferFailed {}.abi_encode())
+    );
+    assert_eq!(
+        registry.get_arbiter_status(ARBITER),
+        (false, U256::ZERO, U256::ZERO)
+    );
+}
+
+// ============================================================================
+// Slashing
+// ============================================================================
+
+#[test]
+fn slash_reduces_stake_and_reputation() {
+    let (vm, mut registry) = setup();
+    register(&vm, &mut registry, ARBITER, 3 * MIN_STAKE);
+
+    vm.set_sender(ADMIN);
+    registry.slash_arbiter(ARBITER, u(MIN_STAKE), 1).unwrap();
+
+    assert_eq!(
+        registry.get_arbiter_status(ARBITER),
+        (true, u(2 * MIN_STAKE), u(90))
+    );
+}
+
+#[test]
+fn slash_to_zero_reputation_deactivates() {
+    let (vm, mut registry) = setup();
+    register(&vm, &mut registry, ARBITER, MIN_STAKE);
+    register(&vm, &mut registry, ARBITER_2, MIN_STAKE);
+    register(&vm, &mut registry, ARBITER_3, MIN_STAKE);
+
+    vm.set_sender(ADMIN);
+    registry.slash_arbiter(ARBITER, U256::ZERO, 0).unwrap();
+    registry.slash_arbiter(ARBITER, U256::ZERO, 0).unwrap();
+
+    assert_eq!(
+        registry.get_arbiter_status(ARBITER),
+        (false, u(MIN_STAKE), U256::ZERO)
+    );
+    // The last entry is swapped into the removed slot
+    assert_eq!(registry.get_active_arbiters(), vec![ARBITER_3, ARBITER_2]);
+}
+
+#[test]
+fn slash_requires_slasher_role() {
+    let (vm, mut registry) = setup();
+    register(&vm, &mut registry, ARBITER, MIN_STAKE);
+
+    vm.set_sender(STRANGER);
+    assert_eq!(
+        registry.slash_arbiter(ARBITER, u(1), 0),
+        Err(MissingRole {
+            role: SLASHER_ROLE,
+            account: STRANGER,
+        }
+        .abi_encode())
+    );
+}
+
+#[test]
+fn granted_slasher_slashes_without_admin_key() {
+    let (vm, mut registry) = setup();
+    register(&vm, &mut registry, ARBITER, 3 * MIN_STAKE);
+
+    vm.set_sender(ADMIN);
+    registry.grant_role(SLASHER_ROLE, STRANGER).unwrap();
+    registry.revoke_role(SLASHER_ROLE, ADMIN).unwrap();
+    assert!(registry.has_role(SLASHER_ROLE, STRANGER));
+    assert!(!registry.has_role(SLASHER_ROLE, ADMIN));
+
+    let granted = events::<RoleGranted>(&vm);
+    let last = granted.last().unwrap();
+    assert_eq!(
+        (last.role, last.account, last.sender),
+        (SLASHER_ROLE, STRANGER, ADMIN)
+    );
+
+    vm.set_sender(STRANGER);
+    registry.slash_arbiter(ARBITER, u(MIN_STAKE), 1).unwrap();
+    assert_eq!(
+        registry.get_arbiter_status(ARBITER),
+        (true, u(2 * MIN_STAKE), u(90))
+    );
+
+    vm.set_sender(ADMIN);
+    assert!(registry.slash_arbiter(ARBITER, u(1), 1).is_err());
+}
+
+#[test]
+fn roles_are_managed_by_admin() {
+    let (vm, mut registry) = setup();
+    assert!(registry.has_role(DEFAULT_ADMIN_ROLE, ADMIN));
+    assert!(registry.has_role(SLASHER_ROLE, ADMIN));
+
+    vm.set_sender(STRANGER);
+    assert_eq!(
+        registry.grant_role(SLASHER_ROLE, STRANGER),
+        Err(NotAdmin { caller: STRANGER }.abi_encode())
+    );
+
+    vm.set_sender(ADMIN);
+    assert_eq!(
+        registry.grant_role(DEFAULT_ADMIN_ROLE, STRANGER),
+        Err(InvalidRole {
+            role: DEFAULT_ADMIN_ROLE
+        }
+        .abi_encode())
+    );
+}
+
+#[test]
+fn slash_cannot_exceed_stake() {
+    let (vm, mut registry) = setup();
+    register(&vm, &mut registry, ARBITER, MIN_STAKE);
+
+    vm.set_sender(ADMIN);
+    assert_eq!(
+        registry.slash_arbiter(ARBITER, u(MIN_STAKE + 1), 0),
+        Err(LowStake {
+            provided: u(MIN_STAKE),
+            required: u(MIN_STAKE + 1),
+        }
+        .abi_encode())
+    );
+}
+
+// ============================================================================
+// Resolution Tracking
+// ============================================================================
+
+#[test]
+fn set_escrow_requires_admin() {
+    let (vm, mut registry) = setup();
+
+    vm.set_sender(STRANGER);
+    assert_eq!(
+        registry.set_escrow(ESCROW),
+        Err(NotAdmin { caller: STRANGER }.abi_encode())
+    );
+
+    vm.set_sender(ADMIN);
+    registry.set_escrow(ESCROW).unwrap();
+    assert_eq!(registry.get_escrow(), ESCROW);
+}
+
+#[test]
+fn record_resolution_counts_disputes() {
+    let (vm, mut registry) = setup();
+    register(&vm, &mut registry, ARBITER, MIN_STAKE);
+    vm.set_sender(ADMIN);
+    registry.set_escrow(ESCROW).unwrap();
+
+    vm.set_sender(ESCROW);
+    registry.record_resolution(ARBITER, u(0), u(500)).unwrap();
+    registry.record_resolution(ARBITER, u(1), u(700)).unwrap();
+
+    assert_eq!(registry.get_disputes_resolved(ARBITER), u(2));
+    let recorded = events::<ResolutionRecorded>(&vm);
+    assert_eq!(recorded.len(), 2);
+    assert_eq!(recorded[1].arbiter, ARBITER);
+    assert_eq!(recorded[1].deal_id, u(1));
+    assert_eq!(recorded[1].amount, u(700));
+}
+
+#[test]
+fn record_resolution_requires_escrow() {
+    let (vm, mut registry) = setup();
+
+    // No escrow configured yet: nobody may report, not even the zero address
+    vm.set_sender(Address::ZERO);
+    assert_eq!(
+        registry.record_resolution(ARBITER, u(0), u(1)),
+        Err(NotEscrow {
+            caller: Address::ZERO
+        }
+        .abi_encode())
+    );
+
+    vm.set_sender(ADMIN);
+    registry.set_escrow(ESCROW).unwrap();
+    vm.set_sender(STRANGER);
+    assert_eq!(
+        registry.record_resolution(ARBITER, u(0), u(1)),
+        Err(NotEscrow { caller: STRANGER }.abi_encode())
+    );
+    assert_eq!(registry.get_disputes_resolved(ARBITER), U256::ZERO);
+}
+
+// ============================================================================
+// Selection
+// ============================================================================
+
+#[test]
+fn select_arbiter_is_stake_weighted() {
+    let (vm, mut registry) = setup();
+    register(&vm, &mut registry, ARBITER, MIN_STAKE);
+    register(&vm, &mut registry, ARBITER_2, 3 * MIN_STAKE);
+
+    // Seeds below the first stake pick the first arbiter, the rest pick the second
+    assert_eq!(registry.select_arbiter(u(0), vec![]), Ok(ARBITER));
+    assert_eq!(
+        registry.select_arbiter(u(MIN_STAKE - 1), vec![]),
+        Ok(ARBITER)
+    );
+    assert_eq!(registry.select_arbiter(u(MIN_STAKE), vec![]), Ok(ARBITER_2));
+    assert_eq!(
+        registry.select_arbiter(u(4 * MIN_STAKE - 1), vec![]),
+        Ok(ARBITER_2)
+    );
+    // Seeds wrap around the total stake
+    assert_eq!(
+        registry.select_arbiter(u(4 * MIN_STAKE), vec![]),
+        Ok(ARBITER)
+    );
+}
+
+#[test]
+fn select_arbiter_skips_excluded() {
+    let (vm, mut registry) = setup();
+    register(&vm, &mut registry, ARBITER, MIN_STAKE);
+    register(&vm, &mut registry, ARBITER_2, MIN_STAKE);
+
+    for seed in 0..4 {
+        assert_eq!(
+            registry.select_arbiter(u(seed * 500), vec![ARBITER]),
+            Ok(ARBITER_2)
+        );
+    }
+}
+
+#[test]
+fn select_arbiter_fails_without_candidates() {
+    let (vm, mut registry) = setup();
+    assert_eq!(
+        registry.select_arbiter(u(7), vec![]),
+        Err(NoActiveArbiters {}.abi_encode())
+    );
+
+    register(&vm, &mut registry, ARBITER, MIN_STAKE);
+    assert_eq!(
+        registry.select_arbiter(u(7), vec![ARBITER]),
+        Err(NoActiveArbiters {}.abi_encode())
+    );
+}
+
+#[test]
+fn select_arbiter_ignores_deactivated() {
+    let (vm, mut registry) = setup();
+    register(&vm, &mut registry, ARBITER, MIN_STAKE);
+    register(&vm, &mut registry, ARBITER_2, MIN_STAKE);
+
+    vm.set_sender(ADMIN);
+    registry.slash_arbiter(ARBITER, U256::ZERO, 0).unwrap();
+    registry.slash_arbiter(ARBITER, U256::ZERO, 0).unwrap();
+
+    for seed in 0..4 {
+        assert_eq!(
+            registry.select_arbiter(u(seed * 500), vec![]),
+            Ok(ARBITER_2)
+        );
+    }
+}
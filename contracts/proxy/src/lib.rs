@@ -0,0 +1,118 @@
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+#![cfg_attr(not(any(test, feature = "export-abi")), no_std)]
+
+#[macro_use]
+extern crate alloc;
+
+use alloc::vec::Vec;
+use alloy_sol_types::{sol, SolError};
+use stylus_sdk::{
+    alloy_primitives::{b256, Address, B256, U256},
+    prelude::*,
+    ArbResult,
+};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// ERC-1967 implementation slot: keccak256("eip1967.proxy.implementation") - 1
+pub const IMPLEMENTATION_SLOT: B256 =
+    b256!("360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc");
+
+// ============================================================================
+// Storage Layout
+// ============================================================================
+
+sol_storage! {
+    /// ERC-1967 proxy in front of ArbiSecure. It declares no fields of its own: the
+    /// implementation address lives in the ERC-1967 slot, so the implementation's storage
+    /// layout starts at slot 0 exactly as it would without the proxy. Upgrades are queued with
+    /// the implementation's `schedule_upgrade` and applied by `execute_operation` once the
+    /// timelock has elapsed.
+    #[entrypoint]
+    pub struct ArbiSecureProxy {}
+}
+
+// ============================================================================
+// Errors
+// ============================================================================
+
+sol! {
+    /// Error thrown when the proxy is deployed without initializer calldata
+    #[derive(Debug)]
+    error MissingInitializer();
+
+    /// Error thrown when the proxy is called before it points at an implementation
+    #[derive(Debug)]
+    error NotInitialized();
+
+    /// Error thrown when the implementation address is zero
+    #[derive(Debug)]
+    error InvalidImplementation(address implementation);
+}
+
+// ============================================================================
+// Implementation
+// ============================================================================
+
+#[public]
+impl ArbiSecureProxy {
+    /// Points the proxy at its first implementation and delegate-calls `data` on it, the
+    /// calldata of the implementation's `initialize(admin)`. Both happen in the deployment
+    /// transaction, leaving no window in which another account could initialize the proxy.
+    #[constructor]
+    pub fn constructor(&mut self, implementation: Address, data: Vec<u8>) -> Result<(), Vec<u8>> {
+        if implementation == Address::ZERO {
+            return Err(InvalidImplementation { implementation }.abi_encode());
+        }
+        if data.is_empty() {
+            return Err(MissingInitializer {}.abi_encode());
+        }
+
+        unsafe {
+            self.vm()
+                .storage_cache_bytes32(implementation_slot(), implementation.into_word());
+        }
+        self.vm().flush_cache(false);
+
+        log(self.vm(), Upgraded { implementation });
+
+        unsafe { self.vm().delegate_call(&self, implementation, &data) }?;
+        Ok(())
+    }
+
+    /// Retrieves the implementation the proxy delegates to.
+    pub fn implementation(&self) -> Address {
+        Address::from_word(self.vm().storage_load_bytes32(implementation_slot()))
+    }
+
+    /// Forwards every other call, with its value, to the implementation.
+    #[payable]
+    #[fallback]
+    fn fallback(&mut self, calldata: &[u8]) -> ArbResult {
+        let implementation = self.implementation();
+        if implementation == Address::ZERO {
+            return Err(NotInitialized {}.abi_encode());
+        }
+
+        let result = unsafe { self.vm().delegate_call(&self, implementation, calldata) }?;
+        Ok(result)
+    }
+}
+
+// ============================================================================
+// Helper Functions (Internal)
+// ============================================================================
+
+fn implementation_slot() -> U256 {
+    U256::from_be_bytes(IMPLEMENTATION_SLOT.0)
+}
+
+// ============================================================================
+// Events
+// ============================================================================
+
+sol! {
+    event Upgraded(address indexed implementation);
+}
@@ -0,0 +1,71 @@
+//! `ArbiSecureProxy` unit tests on the stylus-test `TestVM`.
+
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::SolError;
+use arbisecure_proxy::{
+    ArbiSecureProxy, InvalidImplementation, MissingInitializer, IMPLEMENTATION_SLOT,
+};
+use stylus_sdk::{prelude::*, testing::*};
+
+const PROXY: Address = Address::repeat_byte(0x9E);
+const IMPLEMENTATION: Address = Address::repeat_byte(0x11);
+const DEPLOYER: Address = Address::repeat_byte(0xAD);
+
+/// Calldata standing in for the implementation's `initialize(admin)`
+const INITIALIZER: [u8; 4] = [0xc4, 0xd6, 0x6d, 0xe8];
+
+fn setup() -> (TestVM, ArbiSecureProxy) {
+    let vm = TestVM::default();
+    vm.set_contract_address(PROXY);
+    vm.set_sender(DEPLOYER);
+    let proxy = ArbiSecureProxy::from(&vm);
+    (vm, proxy)
+}
+
+#[test]
+fn constructor_stores_implementation_and_initializes_it() {
+    let (vm, mut proxy) = setup();
+    vm.mock_delegate_call(IMPLEMENTATION, INITIALIZER.to_vec(), Ok(Vec::new()));
+    proxy
+        .constructor(IMPLEMENTATION, INITIALIZER.to_vec())
+        .unwrap();
+
+    assert_eq!(proxy.implementation(), IMPLEMENTATION);
+    let slot = U256::from_be_bytes(IMPLEMENTATION_SLOT.0);
+    assert_eq!(
+        Address::from_word(vm.storage_load_bytes32(slot)),
+        IMPLEMENTATION
+    );
+}
+
+#[test]
+fn constructor_fails_when_initializer_reverts() {
+    let (vm, mut proxy) = setup();
+    vm.mock_delegate_call(IMPLEMENTATION, INITIALIZER.to_vec(), Err(Vec::new()));
+    assert!(proxy
+        .constructor(IMPLEMENTATION, INITIALIZER.to_vec())
+        .is_err());
+}
+
+#[test]
+fn constructor_requires_initializer() {
+    let (_vm, mut proxy) = setup();
+    assert_eq!(
+        proxy.constructor(IMPLEMENTATION, Vec::new()),
+        Err(MissingInitializer {}.abi_encode())
+    );
+    assert_eq!(proxy.implementation(), Address::ZERO);
+}
+
+#[test]
+fn constructor_rejects_zero_implementation() {
+    let (_vm, mut proxy) = setup();
+    assert_eq!(
+        proxy.constructor(Address::ZERO, INITIALIZER.to_vec()),
+        Err(InvalidImplementation {
+            implementation: Address::ZERO
+        }
+        .abi_encode())
+    );
+    assert_eq!(proxy.implementation(), Address::ZERO);
+}
@@ -4,11 +4,17 @@
 #[macro_use]
 extern crate alloc;
 
+pub mod state_machine;
+
 use alloc::vec::Vec;
-use alloy_sol_types::sol;
+use alloy_sol_types::{sol, SolCall, SolError, SolValue};
+use state_machine::{Action, Role};
 use stylus_sdk::{
-    alloy_primitives::{Address, U256, U8},
+    abi::Bytes,
+    alloy_primitives::{b256, Address, B256, U128, U256, U64, U8},
+    crypto::keccak,
     prelude::*,
+    storage::Erase,
 };
 
 // ============================================================================
@@ -55,21 +61,295 @@ impl DealStatus {
     }
 }
 
+/// Ruling on an individual disputed milestone
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+pub enum MilestoneOutcome {
+    /// Not ruled individually (covered by the deal's aggregate ruling)
+    None = 0,
+    /// Milestone amount refunded to the client
+    Refund = 1,
+    /// Milestone amount released to the freelancer
+    Release = 2,
+    /// Milestone amount split between client and freelancer
+    Split = 3,
+}
+
+impl MilestoneOutcome {
+    /// Convert from u8 to MilestoneOutcome
+    #[inline]
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(MilestoneOutcome::None),
+            1 => Some(MilestoneOutcome::Refund),
+            2 => Some(MilestoneOutcome::Release),
+            3 => Some(MilestoneOutcome::Split),
+            _ => None,
+        }
+    }
+
+    /// Convert to u8 for storage
+    #[inline]
+    pub fn as_u8(self) -> u8 {
+        self as u8
+    }
+}
+
+/// Who bears the arbiter fee when a deal's dispute is settled, chosen at creation
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+pub enum FeeMode {
+    /// Each party pays in proportion to their payout
+    Proportional = 0,
+    /// The party receiving less of the disputed amount pays (proportional on an even split)
+    LoserPays = 1,
+    /// The client pays
+    ClientPays = 2,
+}
+
+impl FeeMode {
+    /// Convert from u8 to FeeMode
+    #[inline]
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(FeeMode::Proportional),
+            1 => Some(FeeMode::LoserPays),
+            2 => Some(FeeMode::ClientPays),
+            _ => None,
+        }
+    }
+
+    /// Convert to u8 for storage
+    #[inline]
+    pub fn as_u8(self) -> u8 {
+        self as u8
+    }
+}
+
+/// Privileged call that can only take effect through the timelock
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+pub enum OperationKind {
+    /// Params: (address registry)
+    SetArbiterRegistry = 0,
+    /// Params: (uint256 window, uint256 bond_bps, address court)
+    SetAppealConfig = 1,
+    /// Params: (address token, bool allowed)
+    SetTokenAllowed = 2,
+    /// Params: (address new_implementation, bytes data)
+    Upgrade = 3,
+    /// Params: (uint64 delay)
+    SetTimelockDelay = 4,
+    /// Params: (address token, uint256 amount, uint256 bps)
+    SetDisputeBond = 5,
+}
+
+impl OperationKind {
+    /// Convert from u8 to OperationKind
+    #[inline]
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(OperationKind::SetArbiterRegistry),
+            1 => Some(OperationKind::SetAppealConfig),
+            2 => Some(OperationKind::SetTokenAllowed),
+            3 => Some(OperationKind::Upgrade),
+            4 => Some(OperationKind::SetTimelockDelay),
+            5 => Some(OperationKind::SetDisputeBond),
+            _ => None,
+        }
+    }
+
+    /// Convert to u8 for storage
+    #[inline]
+    pub fn as_u8(self) -> u8 {
+        self as u8
+    }
+
+    /// Role required to schedule or cancel the operation
+    pub fn role(self) -> B256 {
+        match self {
+            OperationKind::SetTokenAllowed => TOKEN_LISTER_ROLE,
+            _ => DEFAULT_ADMIN_ROLE,
+        }
+    }
+}
+
+// ============================================================================
+// Fee Math
+// ============================================================================
+
+/// Basis points denominator
+pub const BPS_DENOMINATOR: u64 = 10000;
+/// Protocol fee on milestone releases (0.5%)
+pub const RELEASE_FEE_BPS: u64 = 50;
+/// Arbiter fee on dispute payouts (5%)
+pub const ARBITER_FEE_BPS: u64 = 500;
+/// Share of the arbiter fee paid to the appeal court when it rules on an appeal (50%)
+pub const APPEAL_COURT_FEE_BPS: u64 = 5000;
+
+/// Net amounts paid out when a dispute is settled
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DisputePayout {
+    /// Amount sent to the client after fees
+    pub net_client: U256,
+    /// Amount sent to the freelancer after fees
+    pub net_freelancer: U256,
+    /// Arbiter fee
+    pub fee: U256,
+}
+
+/// Splits the full `disputed_amount` between client, freelancer and arbiter.
+///
+/// The part of `disputed_amount` not covered by `client_share + freelancer_share` is
+/// refunded to the client. The fee is computed once on the whole amount and divided
+/// according to `fee_mode`. Proportionally, the client's deduction is rounded down so the
+/// freelancer's absorbs the rounding dust. When one party pays, a fee larger than their
+/// payout is topped up by the other. Either way `net_client + net_freelancer + fee ==
+/// disputed_amount` always holds.
+/// Reverts with a typed arithmetic error if the shares exceed the disputed amount.
+pub fn split_dispute_payout(
+    disputed_amount: U256,
+    client_share: U256,
+    freelancer_share: U256,
+    fee_bps: U256,
+    fee_mode: FeeMode,
+) -> Result<DisputePayout, Vec<u8>> {
+    let bps = U256::from(BPS_DENOMINATOR);
+    let allocated = safe_add(client_share, freelancer_share)?;
+    let unallocated = safe_sub(disputed_amount, allocated)?;
+    let client_gross = safe_add(client_share, unallocated)?;
+
+    let fee = safe_mul(disputed_amount, fee_bps)? / bps;
+    let proportional = safe_mul(client_gross, fee_bps)? / bps;
+    let client_fee = match fee_mode {
+        FeeMode::Proportional => proportional,
+        FeeMode::LoserPays if client_gross < freelancer_share => fee.min(client_gross),
+        FeeMode::LoserPays if freelancer_share < client_gross => {
+            safe_sub(fee, fee.min(freelancer_share))?
+        }
+        FeeMode::LoserPays => proportional,
+        FeeMode::ClientPays => fee.min(client_gross),
+    };
+    let freelancer_fee = safe_sub(fee, client_fee)?;
+
+    Ok(DisputePayout {
+        net_client: safe_sub(client_gross, client_fee)?,
+        net_freelancer: safe_sub(freelancer_share, freelancer_fee)?,
+        fee,
+    })
+}
+
+/// Adds two balances, reverting with `ArithmeticOverflow` instead of wrapping.
+#[inline]
+pub fn safe_add(a: U256, b: U256) -> Result<U256, Vec<u8>> {
+    a.checked_add(b)
+        .ok_or_else(|| ArithmeticOverflow {}.abi_encode())
+}
+
+/// Subtracts two balances, reverting with `ArithmeticUnderflow` instead of wrapping.
+#[inline]
+pub fn safe_sub(a: U256, b: U256) -> Result<U256, Vec<u8>> {
+    a.checked_sub(b)
+        .ok_or_else(|| ArithmeticUnderflow {}.abi_encode())
+}
+
+/// Multiplies two balances, reverting with `ArithmeticOverflow` instead of wrapping.
+#[inline]
+pub fn safe_mul(a: U256, b: U256) -> Result<U256, Vec<u8>> {
+    a.checked_mul(b)
+        .ok_or_else(|| ArithmeticOverflow {}.abi_encode())
+}
+
+// ============================================================================
+// Arbiter Selection
+// ============================================================================
+
+/// Time the parties have, from the first commitment, to commit and reveal their arbiter
+/// selection secrets, in seconds (1 day)
+pub const SEED_REVEAL_WINDOW: u64 = 86_400;
+
+// ============================================================================
+// Appeals
+// ============================================================================
+
+/// Time the appeal court has to rule once a ruling is appealed, in seconds (14 days). After it
+/// passes, the original ruling can be executed.
+pub const APPEAL_RULING_WINDOW: u64 = 1_209_600;
+
+// ============================================================================
+// Upgrades
+// ============================================================================
+
+/// ERC-1967 implementation slot: keccak256("eip1967.proxy.implementation") - 1
+pub const IMPLEMENTATION_SLOT: B256 =
+    b256!("360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc");
+
+/// Storage layout version written by `initialize`. Bump it together with a new step in
+/// `migrate` whenever an upgrade needs to transform existing storage.
+pub const STORAGE_VERSION: u64 = 3;
+
+// ============================================================================
+// Access Control
+// ============================================================================
+
+/// Held by `admin` alone; grants and revokes every other role. Moved with `propose_admin`.
+pub const DEFAULT_ADMIN_ROLE: B256 = B256::ZERO;
+
+/// keccak256("FEE_MANAGER_ROLE"): withdraws accrued protocol fees
+pub const FEE_MANAGER_ROLE: B256 =
+    b256!("6c0757dc3e6b28b2580c03fd9e96c274acf4f99d91fbec9b418fa1d70604ff1c");
+
+/// keccak256("PAUSER_ROLE"): pauses and unpauses fund movements
+pub const PAUSER_ROLE: B256 =
+    b256!("65d7a28e3265b37a6474929f336521b332c1681b933f6cb9f3376673440d862a");
+
+/// keccak256("TOKEN_LISTER_ROLE"): lists the ERC20 tokens accepted for new deals
+pub const TOKEN_LISTER_ROLE: B256 =
+    b256!("9d05d93b0bd5bc39711f9138166bb6e08fadf4de614e2a0c59143b59215d7b21");
+
+/// Timelock delay set on initialization, in seconds (2 days)
+pub const DEFAULT_TIMELOCK_DELAY: u64 = 172_800;
+
+/// Bounds of the configurable timelock delay, in seconds (1 to 30 days)
+pub const MIN_TIMELOCK_DELAY: u64 = 86_400;
+pub const MAX_TIMELOCK_DELAY: u64 = 2_592_000;
+
+/// Roles granted to the admin on initialization
+const OPERATIONAL_ROLES: [B256; 3] = [FEE_MANAGER_ROLE, PAUSER_ROLE, TOKEN_LISTER_ROLE];
+
 // ============================================================================
 // Storage Layout
 // ============================================================================
 
 sol_storage! {
-    /// Milestone within a deal (Flattened for size optimization)
+    /// Milestone within a deal, packed into two slots: amounts, then timestamps and flags
     pub struct Milestone {
         /// Amount allocated to this milestone
-        uint256 amount;
+        uint128 amount;
+        /// Portion of the milestone awarded to the client by its ruling
+        uint128 client_award;
+        /// Timestamp when this milestone can be released (0 if none)
+        uint64 end_timestamp;
         /// Whether the milestone funds have been released
         bool is_released;
-        /// Timestamp when this milestone can be released (0 if none)
-        uint256 end_timestamp;
         /// Whether manual approval by client is required
         bool requires_approval;
+        /// Whether the milestone is frozen by the current dispute
+        bool is_disputed;
+        /// Per-milestone ruling (MilestoneOutcome enum)
+        uint8 outcome;
+        /// Time from which `amount` vests linearly until `end_timestamp` (0 if not streamed)
+        uint64 start_timestamp;
+    }
+
+    /// Ruling submitted by a member of an arbiter panel
+    pub struct PanelVote {
+        /// Panelist who submitted the ruling
+        address arbiter;
+        /// Amount awarded to the client
+        uint256 client_share;
+        /// Amount awarded to the freelancer
+        uint256 freelancer_share;
     }
 
     /// Main Deal structure (Includes Dispute Data)
@@ -82,20 +362,117 @@ sol_storage! {
         address arbiter;
         /// Token used for payment (Address::ZERO for ETH)
         address token;
-        /// Remaining deal amount (decrements on release)
-        uint256 remaining_amount;
+        // The token shares its slot with the timestamp, status and dispute flags below
+        /// Creation timestamp
+        uint64 created_at;
         /// Current status of the deal (DealStatus enum)
         uint8 status;
-        /// Creation timestamp
-        uint256 created_at;
+        /// Ruling outcome (0=Pending, 1=Client, 2=Freelancer, 3=Split)
+        uint8 ruling;
+        /// Whether the dispute has been resolved
+        bool is_resolved;
+        /// Remaining deal amount (decrements on release)
+        uint256 remaining_amount;
         /// Milestones associated with the deal
         Milestone[] milestones;
 
         // --- Dispute Data (Flattened) ---
-        /// Whether the dispute has been resolved
-        bool is_resolved;
-        /// Ruling outcome (0=Pending, 1=Client, 2=Freelancer, 3=Split)
-        uint8 ruling;
+        /// Sum of the milestones frozen by the current dispute
+        uint256 disputed_amount;
+
+        // --- Arbiter Reassignment ---
+        /// Replacement arbiter proposed by one of the parties
+        address proposed_arbiter;
+        /// Party that proposed the replacement arbiter
+        address arbiter_proposer;
+
+        // --- Arbiter Panel ---
+        /// Panel of 3 or 5 arbiters (empty for single-arbiter deals)
+        address[] panel;
+        /// Rulings submitted by panelists for the current dispute
+        PanelVote[] votes;
+        /// Arbiter seat targeted by the pending reassignment proposal
+        address replaced_arbiter;
+
+        // --- Random Arbiter Selection (commit-reveal) ---
+        /// Client's commitment to their selection secret
+        bytes32 client_seed_commit;
+        /// Freelancer's commitment to their selection secret
+        bytes32 freelancer_seed_commit;
+        /// Whether the client has revealed their secret
+        bool client_seed_revealed;
+        /// Whether the freelancer has revealed their secret
+        bool freelancer_seed_revealed;
+        /// XOR of the revealed secrets
+        uint256 arbiter_seed;
+
+        // --- Appeal ---
+        /// Client share of the ruling awaiting execution
+        uint256 pending_client_share;
+        /// Freelancer share of the ruling awaiting execution
+        uint256 pending_freelancer_share;
+        /// Arbiters who issued the ruling awaiting execution
+        address[] ruling_arbiters;
+        /// Party that appealed the ruling (Address::ZERO if not appealed)
+        address appellant;
+        /// Time after which the pending ruling executes (0 if none)
+        uint64 appeal_deadline;
+        /// Bond posted by the appellant
+        uint256 appeal_bond;
+
+        // Mutual settlement
+        /// Party whose settlement offer is open (Address::ZERO if none)
+        address settlement_proposer;
+        /// Client's share of the remaining amount under the open offer
+        uint256 settlement_client_share;
+        /// Freelancer's share of the remaining amount under the open offer
+        uint256 settlement_freelancer_share;
+
+        // Dispute withdrawal
+        /// Party that raised the open dispute
+        address dispute_initiator;
+        /// Status the deal returns to if the dispute is withdrawn
+        uint8 pre_dispute_status;
+
+        // Dispute bonds
+        /// Bond posted by the dispute initiator, which the counterparty may match
+        uint256 dispute_bond;
+        /// Whether the counterparty has matched the dispute bond
+        bool bond_matched;
+
+        // Fee allocation
+        /// Who bears the arbiter fee (FeeMode enum)
+        uint8 fee_mode;
+
+        // Streaming
+        /// When the open dispute was raised, to resume paused streams on withdrawal
+        uint64 disputed_at;
+
+        // Retainer
+        /// Amount funded per period (0 for fixed-scope deals)
+        uint256 period_amount;
+        /// Length of a period in seconds
+        uint64 period_length;
+        /// Full periods that must still be paid after either party ends the retainer
+        uint64 notice_periods;
+        /// End of the last funded period
+        uint64 funded_until;
+        /// Time the retainer ends (0 while it runs)
+        uint64 retainer_end;
+
+        // Arbiter selection deadline
+        /// Time after which a stalled commit-reveal round can be concluded (0 if none)
+        uint64 seed_deadline;
+    }
+
+    /// Privileged call queued behind the timelock
+    pub struct Operation {
+        /// OperationKind value
+        uint8 kind;
+        /// Earliest execution time (0 if not queued)
+        uint64 eta;
+        /// ABI-encoded call parameters (see OperationKind)
+        bytes params;
     }
 
     /// Main contract storage for ArbiSecure escrow protocol
@@ -114,6 +491,63 @@ sol_storage! {
         // === Gasless Transaction Support ===
         /// Tracks whether an address has used their free first deal
         mapping(address => bool) gasless_deals_used;
+
+        // === Arbiter Registry ===
+        /// ArbiterRegistry notified of dispute resolutions (Address::ZERO if unset)
+        address arbiter_registry;
+
+        // === Appeals ===
+        /// Seconds a ruling stays locked and appealable (0 executes rulings immediately)
+        uint256 appeal_window;
+        /// Appeal bond as basis points of the ruled amount
+        uint256 appeal_bond_bps;
+        /// Appeal court that rules on appealed disputes
+        address appeal_court;
+
+        // === Solvency Accounting ===
+        /// Funds held on behalf of deals (deal amounts and bonds), per token
+        mapping(address => uint256) total_escrowed;
+        /// Protocol fees collected and not yet withdrawn, per token
+        mapping(address => uint256) accrued_fees;
+
+        // === Upgrades ===
+        /// Storage layout version (see STORAGE_VERSION); new fields are only ever appended
+        uint64 storage_version;
+
+        // === Access Control ===
+        /// Role members per role ID; DEFAULT_ADMIN_ROLE is held by `admin` instead
+        mapping(bytes32 => mapping(address => bool)) roles;
+        /// Whether fund movements are paused
+        bool paused;
+        /// ERC20 tokens accepted for new deals (native ETH is always accepted)
+        mapping(address => bool) allowed_tokens;
+
+        // === Admin Transfer ===
+        /// Nominee awaiting `accept_admin` (Address::ZERO if none)
+        address pending_admin;
+
+        // === Timelock ===
+        /// Seconds between scheduling a privileged operation and its earliest execution
+        uint64 timelock_delay;
+        /// Counter for generating unique operation IDs
+        uint256 operation_counter;
+        /// Mapping of operation ID to queued Operation
+        mapping(uint256 => Operation) operations;
+
+        // === Dispute Bonds ===
+        /// Flat dispute bond, per token
+        mapping(address => uint256) dispute_bond_amounts;
+        /// Dispute bond as basis points of the deal's remaining amount, per token
+        mapping(address => uint256) dispute_bond_bps;
+    }
+}
+
+/// Lets a deal's `votes` be cleared once its dispute is settled
+impl Erase for PanelVote {
+    fn erase(&mut self) {
+        self.arbiter.erase();
+        self.client_share.erase();
+        self.freelancer_share.erase();
     }
 }
 
@@ -121,172 +555,656 @@ sol_storage! {
 // Interfaces
 // ============================================================================
 
-sol_interface! {
+sol! {
     interface IERC20 {
         function transfer(address recipient, uint256 amount) external returns (bool);
         function transferFrom(address sender, address recipient, uint256 amount) external returns (bool);
+        function balanceOf(address account) external view returns (uint256);
+    }
+
+    interface IArbiterRegistry {
+        function recordResolution(address arbiter, uint256 deal_id, uint256 amount) external;
+        function getArbiterStatus(address arbiter) external view returns (bool, uint256, uint256);
+        function selectArbiter(uint256 seed, address[] exclude) external view returns (address);
     }
 }
 
+// ============================================================================
+// Errors
+// ============================================================================
+
+sol! {
+    /// Error thrown when balance arithmetic would overflow
+    #[derive(Debug)]
+    error ArithmeticOverflow();
+
+    /// Error thrown when balance arithmetic would underflow
+    #[derive(Debug)]
+    error ArithmeticUnderflow();
+
+    /// Error thrown when an outgoing ETH or token transfer fails
+    #[derive(Debug)]
+    error TransferFailed(address token, address recipient, uint256 amount);
+}
+
 // ============================================================================
 // Implementation
 // ============================================================================
 
 #[public]
 impl ArbiSecure {
-    /// Initializes the contract and sets the deployer as the initial admin.
-    pub fn initialize(&mut self) {
-        let caller = self.vm().msg_sender();
-        if self.admin.get() != Address::ZERO {
-            return;
-        }
-        self.admin.set(caller);
-        self.deal_counter.set(U256::ZERO);
+    /// Initializes a direct deployment with `admin` in the deployment transaction. Behind the
+    /// proxy this only initializes the implementation's own storage, so it cannot be claimed.
+    #[constructor]
+    pub fn constructor(&mut self, admin: Address) {
+        self.init(admin);
+    }
+
+    /// Initializes the proxy's storage with `admin`. The proxy's constructor delegate-calls
+    /// this in its deployment transaction, so no one can initialize the proxy first.
+    pub fn initialize(&mut self, admin: Address) {
+        require(self.admin.get() == Address::ZERO, "Init");
+        self.init(admin);
     }
 
-    /// Transfers administrator privileges to a new designated address.
-    pub fn transfer_admin(&mut self, new_admin: Address) {
+    /// Nominates a new administrator. The current admin keeps every privilege until the
+    /// nominee calls `accept_admin`; a new nomination replaces any pending one.
+    pub fn propose_admin(&mut self, new_admin: Address) {
         let caller = self.vm().msg_sender();
         let admin = self.admin.get();
 
         require(caller == admin, "Auth");
         require(new_admin != Address::ZERO, "Zero");
 
-        self.admin.set(new_admin);
-    }
-
-    /// Retrieves the current admnistrator's address.
-    pub fn admin(&self) -> Address {
-        self.admin.get()
+        self.pending_admin.set(new_admin);
+        log(
+            self.vm(),
+            AdminProposed {
+                admin,
+                pending_admin: new_admin,
+            },
+        );
     }
 
-    /// Creates a new escrow deal with programmable milestones.
-    /// Requires that the caller provides the total necessary deal funds,
-    /// either via native ETH or an approved ERC20 token transfer.
-    #[payable]
-    pub fn create_deal(
-        &mut self,
-        _ref_id: U256,
-        freelancer: Address,
-        arbiter: Address,
-        token: Address,
-        amount: U256,
-        milestone_amounts: Vec<U256>,
-        milestone_end_times: Vec<U256>,
-        milestone_approvals: Vec<U256>, // Changed to U256 for ABI safety
-    ) -> U256 {
+    /// Completes an admin transfer. Callable only by the pending admin.
+    pub fn accept_admin(&mut self) {
         let caller = self.vm().msg_sender();
-        self.gasless_deals_used.setter(caller).set(true);
-
-        require(amount > U256::ZERO, "0Amt");
-        require(freelancer != Address::ZERO, "0Free");
-        require(arbiter != Address::ZERO, "0Arb");
+        let pending = self.pending_admin.get();
+        require(pending != Address::ZERO && caller == pending, "Auth");
 
-        // Validate lengths
-        let len = milestone_amounts.len();
-        require(milestone_end_times.len() == len, "Len");
-        require(milestone_approvals.len() == len, "Len");
-        require(len > 0, "NoMs");
+        let previous_admin = self.admin.get();
+        self.admin.set(pending);
+        self.pending_admin.set(Address::ZERO);
 
-        // Validate sum
-        let mut total_milestone_amount = U256::ZERO;
-        for amt in &milestone_amounts {
-            total_milestone_amount += *amt;
-        }
-        require(total_milestone_amount == amount, "Sum");
+        log(
+            self.vm(),
+            AdminTransferred {
+                previous_admin,
+                new_admin: pending,
+            },
+        );
+    }
 
-        // Transfer funds
-        if token == Address::ZERO {
-            require(self.vm().msg_value() == amount, "BadETH");
-        } else {
-            let token_contract = IERC20::new(token);
-            let contract_address = self.vm().contract_address();
-            let result = token_contract.transfer_from(&mut *self, caller, contract_address, amount);
-            match result {
-                Ok(success) => {
-                    require(success, "TknF");
-                }
-                Err(_) => panic!("TknF"),
-            }
-        }
+    /// Withdraws a pending admin nomination.
+    pub fn cancel_admin_proposal(&mut self) {
+        let caller = self.vm().msg_sender();
+        require(caller == self.admin.get(), "Auth");
 
-        // Cache timestamp
-        let timestamp = self.vm().block_timestamp();
+        let pending = self.pending_admin.get();
+        require(pending != Address::ZERO, "None");
 
-        // Create Deal
-        let deal_id = self.deal_counter.get();
-        self.deal_counter.set(deal_id + U256::from(1));
+        self.pending_admin.set(Address::ZERO);
+        log(
+            self.vm(),
+            AdminProposalCancelled {
+                pending_admin: pending,
+            },
+        );
+    }
 
-        let mut deal = self.deals.setter(deal_id);
-        deal.client.set(caller);
-        deal.freelancer.set(freelancer);
-        deal.arbiter.set(arbiter);
-        deal.token.set(token);
-        deal.remaining_amount.set(amount);
-        deal.status.set(U8::from(DealStatus::Funded.as_u8()));
-        deal.created_at.set(U256::from(timestamp));
+    /// Retrieves the nominee awaiting `accept_admin` (Address::ZERO if none).
+    pub fn pending_admin(&self) -> Address {
+        self.pending_admin.get()
+    }
 
-        // Initialize Dispute fields
-        deal.is_resolved.set(false);
-        deal.ruling.set(U8::from(0));
+    /// Retrieves the current admnistrator's address.
+    pub fn admin(&self) -> Address {
+        self.admin.get()
+    }
 
-        // Set Milestones
-        for i in 0..len {
-            let mut m_guard = deal.milestones.grow();
-            m_guard.amount.set(milestone_amounts[i]);
-            m_guard.is_released.set(false);
-            m_guard.end_timestamp.set(milestone_end_times[i]);
-            m_guard
-                .requires_approval
-                .set(milestone_approvals[i] != U256::ZERO);
+    /// Runs the storage migrations between the stored layout version and STORAGE_VERSION.
+    /// Anyone may call it, typically whoever executes the upgrade: the steps are fixed by the
+    /// code and each runs once.
+    pub fn migrate(&mut self) {
+        let from = self.storage_version.get().to::<u64>();
+        require(from < STORAGE_VERSION, "Ver");
+
+        // Each step moves the layout up by one version
+        for version in from..STORAGE_VERSION {
+            match version {
+                // Deployments that predate versioning share the v1 layout
+                0 => {}
+                // v2 splits operational duties into roles, initially all held by the admin, and
+                // only accepts listed ERC20 tokens, starting with those existing deals use
+                1 => {
+                    let admin = self.admin.get();
+                    for role in OPERATIONAL_ROLES {
+                        self.set_role(role, admin, true);
+                    }
+                    self.list_deal_tokens();
+                }
+                // v3 puts privileged parameter changes behind the timelock
+                2 => self.timelock_delay.set(U64::from(DEFAULT_TIMELOCK_DELAY)),
+                _ => panic!("Ver"),
+            }
         }
+        self.storage_version.set(U64::from(STORAGE_VERSION));
 
         log(
             self.vm(),
-            DealCreated {
-                deal_id,
-                client: caller,
-                freelancer,
-                amount,
-                token,
+            Migrated {
+                from_version: from,
+                to_version: STORAGE_VERSION,
             },
         );
+    }
 
-        deal_id
+    /// Retrieves the implementation the ArbiSecure proxy currently delegates to.
+    pub fn implementation(&self) -> Address {
+        let slot = U256::from_be_bytes(IMPLEMENTATION_SLOT.0);
+        Address::from_word(self.vm().storage_load_bytes32(slot))
     }
 
-    /// Releases the specified milestone's funds to the freelancer if all required
-    /// conditions, such as time locks and manual client approvals, are satisfied.
-    pub fn release_milestone(&mut self, deal_id: U256, milestone_index: U256) {
+    /// Retrieves the storage layout version.
+    pub fn storage_version(&self) -> u64 {
+        self.storage_version.get().to::<u64>()
+    }
+
+    /// Grants `role` to `account`. Only the admin manages roles; DEFAULT_ADMIN_ROLE itself
+    /// moves with `propose_admin`.
+    pub fn grant_role(&mut self, role: B256, account: Address) {
         let caller = self.vm().msg_sender();
-        let timestamp = self.vm().block_timestamp();
+        require(caller == self.admin.get(), "Auth");
+        require(role != DEFAULT_ADMIN_ROLE, "Role");
+        require(account != Address::ZERO, "Zero");
 
-        let (freelancer, amount, token_addr) = {
-            let mut deal = self.deals.setter(deal_id);
-            let client = deal.client.get();
-            let freelancer = deal.freelancer.get();
+        self.set_role(role, account, true);
+    }
 
-            let status_val = deal.status.get().to::<u8>();
-            let status = DealStatus::from_u8(status_val).expect("BadS");
+    /// Revokes `role` from `account`.
+    pub fn revoke_role(&mut self, role: B256, account: Address) {
+        let caller = self.vm().msg_sender();
+        require(caller == self.admin.get(), "Auth");
+        require(role != DEFAULT_ADMIN_ROLE, "Role");
 
-            require(
-                status == DealStatus::Funded || status == DealStatus::Active,
-                "BadSt",
-            );
+        self.set_role(role, account, false);
+    }
 
-            let milestone_idx_usize = milestone_index.to::<usize>();
-            require(milestone_idx_usize < deal.milestones.len(), "NoMs");
+    /// Checks whether `account` holds `role`.
+    pub fn has_role(&self, role: B256, account: Address) -> bool {
+        if role == DEFAULT_ADMIN_ROLE {
+            return account == self.admin.get();
+        }
+        self.roles.getter(role).get(account)
+    }
 
-            let mut milestone = deal.milestones.setter(milestone_idx_usize).expect("NoMs");
-            require(!milestone.is_released.get(), "Rel");
+    /// Pauses deal creation, releases, rulings and appeals. Disputes can still be raised.
+    pub fn pause(&mut self) {
+        self.only_role(PAUSER_ROLE);
+        require(!self.paused.get(), "Paused");
 
-            let milestone_amount = milestone.amount.get();
+        self.paused.set(true);
+        log(
+            self.vm(),
+            Paused {
+                account: self.vm().msg_sender(),
+            },
+        );
+    }
 
-            // Check conditions (Flattened)
-            let end_time = milestone.end_timestamp.get();
-            let req_approval = milestone.requires_approval.get();
+    /// Lifts a pause.
+    pub fn unpause(&mut self) {
+        self.only_role(PAUSER_ROLE);
+        require(self.paused.get(), "NotPaused");
 
-            // Logic:
+        self.paused.set(false);
+        log(
+            self.vm(),
+            Unpaused {
+                account: self.vm().msg_sender(),
+            },
+        );
+    }
+
+    /// Checks whether fund movements are paused.
+    pub fn paused(&self) -> bool {
+        self.paused.get()
+    }
+
+    /// Schedules listing or delisting an ERC20 token for new deals. Existing deals are
+    /// unaffected.
+    pub fn schedule_token_allowed(
+        &mut self,
+        token: Address,
+        allowed: bool,
+    ) -> Result<U256, Vec<u8>> {
+        require(token != Address::ZERO, "Zero");
+        self.schedule(
+            OperationKind::SetTokenAllowed,
+            (token, allowed).abi_encode_params(),
+        )
+    }
+
+    /// Checks whether new deals may be funded in `token`.
+    pub fn is_token_allowed(&self, token: Address) -> bool {
+        token == Address::ZERO || self.allowed_tokens.get(token)
+    }
+
+    /// Schedules setting the ArbiterRegistry that records arbiter track records on dispute
+    /// resolution. Passing Address::ZERO disables reporting.
+    pub fn schedule_arbiter_registry(&mut self, registry: Address) -> Result<U256, Vec<u8>> {
+        self.schedule(
+            OperationKind::SetArbiterRegistry,
+            (registry,).abi_encode_params(),
+        )
+    }
+
+    /// Retrieves the ArbiterRegistry address used for arbiter track records.
+    pub fn arbiter_registry(&self) -> Address {
+        self.arbiter_registry.get()
+    }
+
+    /// Schedules configuring the appeal round: how long rulings stay appealable, the appeal
+    /// bond (basis points of the ruled amount) and the appeal court.
+    pub fn schedule_appeal_config(
+        &mut self,
+        window: U256,
+        bond_bps: U256,
+        court: Address,
+    ) -> Result<U256, Vec<u8>> {
+        require(bond_bps <= U256::from(BPS_DENOMINATOR), "Bps");
+        require(window == U256::ZERO || court != Address::ZERO, "0Crt");
+        self.schedule(
+            OperationKind::SetAppealConfig,
+            (window, bond_bps, court).abi_encode_params(),
+        )
+    }
+
+    /// Schedules pointing the proxy at `new_implementation`. A non-empty `data` is
+    /// delegate-called on the new implementation in the executing transaction, typically to
+    /// run `migrate()`. Only meaningful when called through the ArbiSecure proxy.
+    pub fn schedule_upgrade(
+        &mut self,
+        new_implementation: Address,
+        data: Vec<u8>,
+    ) -> Result<U256, Vec<u8>> {
+        require(new_implementation != Address::ZERO, "Zero");
+        self.schedule(
+            OperationKind::Upgrade,
+            (new_implementation, Bytes::from(data)).abi_encode_params(),
+        )
+    }
+
+    /// Schedules setting the bond for disputes on `token` deals: either a flat `amount` or
+    /// `bps` of the deal's remaining amount. Zero for both makes disputes free.
+    pub fn schedule_dispute_bond(
+        &mut self,
+        token: Address,
+        amount: U256,
+        bps: U256,
+    ) -> Result<U256, Vec<u8>> {
+        require(bps <= U256::from(BPS_DENOMINATOR), "Bps");
+        require(amount == U256::ZERO || bps == U256::ZERO, "Bond");
+        self.schedule(
+            OperationKind::SetDisputeBond,
+            (token, amount, bps).abi_encode_params(),
+        )
+    }
+
+    /// Schedules changing the timelock delay itself, within MIN_ and MAX_TIMELOCK_DELAY.
+    pub fn schedule_timelock_delay(&mut self, delay: u64) -> Result<U256, Vec<u8>> {
+        require(
+            (MIN_TIMELOCK_DELAY..=MAX_TIMELOCK_DELAY).contains(&delay),
+            "Delay",
+        );
+        self.schedule(
+            OperationKind::SetTimelockDelay,
+            (delay,).abi_encode_params(),
+        )
+    }
+
+    /// Withdraws a queued operation. Requires the role that can schedule it.
+    pub fn cancel_operation(&mut self, operation_id: U256) {
+        let (kind, eta) = self.queued(operation_id);
+        self.only_role(kind.role());
+        require(eta > 0, "NoOp");
+
+        self.clear_operation(operation_id);
+        log(self.vm(), OperationCancelled { operation_id });
+    }
+
+    /// Applies a queued operation once its ETA has passed. Anyone may execute it.
+    pub fn execute_operation(&mut self, operation_id: U256) -> Result<(), Vec<u8>> {
+        let timestamp = self.vm().block_timestamp();
+        let (kind, eta) = self.queued(operation_id);
+        require(eta > 0, "NoOp");
+        require(timestamp >= eta, "Time");
+
+        let params = self.operations.get(operation_id).params.get_bytes();
+        self.clear_operation(operation_id);
+
+        match kind {
+            OperationKind::SetArbiterRegistry => {
+                let (registry,) = <(Address,)>::abi_decode_params(&params, true).expect("Params");
+                self.arbiter_registry.set(registry);
+            }
+            OperationKind::SetAppealConfig => {
+                let (window, bond_bps, court) =
+                    <(U256, U256, Address)>::abi_decode_params(&params, true).expect("Params");
+                self.appeal_window.set(window);
+                self.appeal_bond_bps.set(bond_bps);
+                self.appeal_court.set(court);
+            }
+            OperationKind::SetTokenAllowed => {
+                let (token, allowed) =
+                    <(Address, bool)>::abi_decode_params(&params, true).expect("Params");
+                self.allowed_tokens.setter(token).set(allowed);
+                log(self.vm(), TokenListed { token, allowed });
+            }
+            OperationKind::Upgrade => {
+                let (implementation, data) =
+                    <(Address, Bytes)>::abi_decode_params(&params, true).expect("Params");
+                self.upgrade(implementation, &data)?;
+            }
+            OperationKind::SetTimelockDelay => {
+                let (delay,) = <(u64,)>::abi_decode_params(&params, true).expect("Params");
+                self.timelock_delay.set(U64::from(delay));
+            }
+            OperationKind::SetDisputeBond => {
+                let (token, amount, bps) =
+                    <(Address, U256, U256)>::abi_decode_params(&params, true).expect("Params");
+                self.dispute_bond_amounts.setter(token).set(amount);
+                self.dispute_bond_bps.setter(token).set(bps);
+            }
+        }
+
+        log(self.vm(), OperationExecuted { operation_id });
+        Ok(())
+    }
+
+    /// Retrieves a queued operation's kind, ETA (0 if not queued) and ABI-encoded parameters.
+    pub fn get_operation(&self, operation_id: U256) -> (u8, u64, Bytes) {
+        let operation = self.operations.get(operation_id);
+        (
+            operation.kind.get().to::<u8>(),
+            operation.eta.get().to::<u64>(),
+            Bytes::from(operation.params.get_bytes()),
+        )
+    }
+
+    /// Retrieves the delay between scheduling and executing privileged operations.
+    pub fn timelock_delay(&self) -> u64 {
+        self.timelock_delay.get().to::<u64>()
+    }
+
+    /// Withdraws all accrued protocol fees for `token` to `to`.
+    pub fn withdraw_fees(&mut self, token: Address, to: Address) -> Result<(), Vec<u8>> {
+        self.only_role(FEE_MANAGER_ROLE);
+        require(to != Address::ZERO, "Zero");
+
+        let amount = self.accrued_fees.get(token);
+        self.accrued_fees.setter(token).set(U256::ZERO);
+        self.send(token, to, amount)?;
+
+        log(self.vm(), FeesWithdrawn { token, to, amount });
+        Ok(())
+    }
+
+    /// Retrieves the funds held on behalf of deals for `token`.
+    pub fn get_total_escrowed(&self, token: Address) -> U256 {
+        self.total_escrowed.get(token)
+    }
+
+    /// Retrieves the protocol fees accrued for `token` and not yet withdrawn.
+    pub fn get_accrued_fees(&self, token: Address) -> U256 {
+        self.accrued_fees.get(token)
+    }
+
+    /// Checks that the contract's balance of `token` covers all escrowed funds plus accrued fees.
+    pub fn is_solvent(&self, token: Address) -> Result<bool, Vec<u8>> {
+        let required = safe_add(self.total_escrowed.get(token), self.accrued_fees.get(token))?;
+        let contract_address = self.vm().contract_address();
+
+        let balance = if token == Address::ZERO {
+            self.vm().balance(contract_address)
+        } else {
+            let call = IERC20::balanceOfCall {
+                account: contract_address,
+            };
+            match self.static_call_contract(token, call) {
+                Ok(balance) => balance._0,
+                Err(_) => panic!("Bal"),
+            }
+        };
+
+        Ok(balance >= required)
+    }
+
+    /// Retrieves the appeal window, appeal bond (basis points) and appeal court.
+    pub fn get_appeal_config(&self) -> (U256, U256, Address) {
+        (
+            self.appeal_window.get(),
+            self.appeal_bond_bps.get(),
+            self.appeal_court.get(),
+        )
+    }
+
+    /// Creates a new escrow deal with programmable milestones.
+    /// Requires that the caller provides the total necessary deal funds,
+    /// either via native ETH or an approved ERC20 token transfer.
+    /// An `arbiter` of Address::ZERO has one drawn from the ArbiterRegistry once both parties
+    /// commit and reveal. See `create_deal_with_terms` for panels, fee modes and streams.
+    #[payable]
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_deal(
+        &mut self,
+        _ref_id: U256,
+        freelancer: Address,
+        arbiter: Address,
+        token: Address,
+        amount: U256,
+        milestone_amounts: Vec<U256>,
+        milestone_end_times: Vec<U256>,
+        milestone_approvals: Vec<U256>, // Changed to U256 for ABI safety
+    ) -> Result<U256, Vec<u8>> {
+        let len = milestone_amounts.len();
+        self.create_deal_with_terms(
+            _ref_id,
+            freelancer,
+            arbiter,
+            token,
+            amount,
+            milestone_amounts,
+            milestone_end_times,
+            vec![U256::ZERO; len],
+            milestone_approvals,
+            Vec::new(),
+            FeeMode::Proportional.as_u8(),
+        )
+    }
+
+    /// Creates a deal like `create_deal`, with the terms it leaves at their defaults.
+    /// A non-empty `panel` of 3 or 5 arbiters replaces the single `arbiter`, `fee_mode` sets who
+    /// bears the arbiter fee (see FeeMode), and a non-zero `milestone_start_times` entry makes
+    /// that milestone stream from its start to its end time.
+    #[payable]
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_deal_with_terms(
+        &mut self,
+        _ref_id: U256,
+        freelancer: Address,
+        arbiter: Address,
+        token: Address,
+        amount: U256,
+        milestone_amounts: Vec<U256>,
+        milestone_end_times: Vec<U256>,
+        milestone_start_times: Vec<U256>,
+        milestone_approvals: Vec<U256>,
+        panel: Vec<Address>,
+        fee_mode: u8,
+    ) -> Result<U256, Vec<u8>> {
+        let caller = self.vm().msg_sender();
+        self.require_not_paused();
+        require(self.is_token_allowed(token), "Token");
+        self.gasless_deals_used.setter(caller).set(true);
+
+        require(amount > U256::ZERO, "0Amt");
+        require(freelancer != Address::ZERO, "0Free");
+        require(FeeMode::from_u8(fee_mode).is_some(), "Fee");
+
+        // Validate arbiter or panel; the first panelist presides as the deal's arbiter
+        let arbiter = if panel.is_empty() {
+            arbiter
+        } else {
+            require(panel.len() == 3 || panel.len() == 5, "Panel");
+            for i in 0..panel.len() {
+                require(panel[i] != Address::ZERO, "0Arb");
+                require(panel[i] != caller && panel[i] != freelancer, "Party");
+                for j in 0..i {
+                    require(panel[i] != panel[j], "Dup");
+                }
+            }
+            panel[0]
+        };
+        require(
+            arbiter != Address::ZERO || self.arbiter_registry.get() != Address::ZERO,
+            "0Arb",
+        );
+
+        // Validate lengths
+        let len = milestone_amounts.len();
+        require(milestone_end_times.len() == len, "Len");
+        require(milestone_start_times.len() == len, "Len");
+        require(milestone_approvals.len() == len, "Len");
+        require(len > 0, "NoMs");
+
+        // Validate sum, and that amounts and timestamps fit their packed storage
+        let mut total_milestone_amount = U256::ZERO;
+        for amt in &milestone_amounts {
+            if *amt > U256::from(u128::MAX) {
+                return Err(ArithmeticOverflow {}.abi_encode());
+            }
+            total_milestone_amount = safe_add(total_milestone_amount, *amt)?;
+        }
+        for end_time in &milestone_end_times {
+            require(*end_time <= U256::from(u64::MAX), "Time");
+        }
+        // Streamed milestones vest over a non-empty window and need no approval
+        for i in 0..len {
+            if milestone_start_times[i] != U256::ZERO {
+                require(milestone_start_times[i] < milestone_end_times[i], "Time");
+                require(milestone_approvals[i] == U256::ZERO, "Strm");
+            }
+        }
+        require(total_milestone_amount == amount, "Sum");
+
+        let status = advance(DealStatus::Created, Action::Create, Role::Client);
+
+        // Transfer funds
+        self.collect(token, caller, amount)?;
+
+        // Cache timestamp
+        let timestamp = self.vm().block_timestamp();
+
+        // Create Deal
+        let deal_id = self.deal_counter.get();
+        self.deal_counter.set(safe_add(deal_id, U256::from(1))?);
+
+        let mut deal = self.deals.setter(deal_id);
+        deal.client.set(caller);
+        deal.freelancer.set(freelancer);
+        deal.arbiter.set(arbiter);
+        deal.token.set(token);
+        deal.remaining_amount.set(amount);
+        deal.status.set(U8::from(status.as_u8()));
+        deal.created_at.set(U64::from(timestamp));
+        deal.fee_mode.set(U8::from(fee_mode));
+
+        // Initialize Dispute fields
+        deal.is_resolved.set(false);
+        deal.ruling.set(U8::from(0));
+
+        // Set Milestones
+        for i in 0..len {
+            let mut m_guard = deal.milestones.grow();
+            m_guard.amount.set(U128::from(milestone_amounts[i]));
+            m_guard.is_released.set(false);
+            m_guard.end_timestamp.set(U64::from(milestone_end_times[i]));
+            m_guard
+                .start_timestamp
+                .set(U64::from(milestone_start_times[i]));
+            m_guard
+                .requires_approval
+                .set(milestone_approvals[i] != U256::ZERO);
+        }
+
+        // Set Panel
+        for panelist in panel.iter() {
+            deal.panel.push(*panelist);
+        }
+
+        log(
+            self.vm(),
+            DealCreated {
+                deal_id,
+                client: caller,
+                freelancer,
+                amount,
+                token,
+            },
+        );
+
+        Ok(deal_id)
+    }
+
+    /// Releases the specified milestone's funds to the freelancer if all required
+    /// conditions, such as time locks and manual client approvals, are satisfied.
+    pub fn release_milestone(
+        &mut self,
+        deal_id: U256,
+        milestone_index: U256,
+    ) -> Result<(), Vec<u8>> {
+        let caller = self.vm().msg_sender();
+        let timestamp = self.vm().block_timestamp();
+        self.require_not_paused();
+        let role = self.role_of(deal_id, caller, Action::Release { completes: false });
+
+        let (freelancer, amount, fee_amount, token_addr) = {
+            let mut deal = self.deals.setter(deal_id);
+            let client = deal.client.get();
+            let freelancer = deal.freelancer.get();
+
+            let status_val = deal.status.get().to::<u8>();
+            let status = DealStatus::from_u8(status_val).expect("BadS");
+
+            let milestone_idx_usize = milestone_index.to::<usize>();
+            require(milestone_idx_usize < deal.milestones.len(), "NoMs");
+
+            // Read deal-level state before the milestone setter borrows `deal`
+            let current_remaining = deal.remaining_amount.get();
+            let running_retainer = is_running_retainer(&deal);
+
+            let mut milestone = deal.milestones.setter(milestone_idx_usize).expect("NoMs");
+            require(!milestone.is_released.get(), "Rel");
+            require(!milestone.is_disputed.get(), "Disp");
+
+            let milestone_amount = U256::from(milestone.amount.get());
+
+            // Check conditions (Flattened)
+            let end_time = milestone.end_timestamp.get().to::<u64>();
+            let req_approval = milestone.requires_approval.get();
+
+            // Logic:
             // 1. Client can ALWAYS release (Manual Override / Approval)
             // 2. If not Client (e.g. Freelancer claiming auto-release):
             //    - Must NOT require manual approval
@@ -294,86 +1212,1670 @@ impl ArbiSecure {
 
             if caller != client {
                 require(!req_approval, "Auth");
-                if end_time > U256::ZERO {
-                    require(U256::from(timestamp) >= end_time, "Time");
+                if end_time > 0 {
+                    require(timestamp >= end_time, "Time");
                 }
             }
 
+            let new_remaining = safe_sub(current_remaining, milestone_amount)?;
+            let completes = new_remaining == U256::ZERO && !running_retainer;
+            let next = advance(status, Action::Release { completes }, role);
+
             // Release funds
             milestone.is_released.set(true);
-            // Decrement remaining amount
-            let current_remaining = deal.remaining_amount.get();
-            let new_remaining = current_remaining - milestone_amount;
             deal.remaining_amount.set(new_remaining);
+            deal.status.set(U8::from(next.as_u8()));
+
+            let token_addr = deal.token.get();
+
+            // Fee (0.5%)
+            let fee_bps = U256::from(RELEASE_FEE_BPS);
+            let fee_amount = safe_mul(milestone_amount, fee_bps)? / U256::from(BPS_DENOMINATOR);
+            let amount = safe_sub(milestone_amount, fee_amount)?;
+
+            (freelancer, amount, fee_amount, token_addr)
+        };
+
+        self.accrue_fee(token_addr, fee_amount)?;
+        self.transfer_out(token_addr, freelancer, amount)?;
+
+        log(
+            self.vm(),
+            MilestoneReleased {
+                deal_id,
+                milestone_index,
+                freelancer,
+                amount,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Pays the freelancer the part of a streamed milestone that has vested since it was
+    /// created or last claimed. The milestone keeps only the unvested remainder, which vests
+    /// over what is left of its window; it is released once fully claimed. Streams frozen by
+    /// a dispute cannot be claimed, and the client can still release a stream early.
+    pub fn claim_streamed(&mut self, deal_id: U256, milestone_index: U256) -> Result<(), Vec<u8>> {
+        let caller = self.vm().msg_sender();
+        let timestamp = self.vm().block_timestamp();
+        self.require_not_paused();
+        let role = self.role_of(deal_id, caller, Action::Release { completes: false });
+
+        let (freelancer, amount, fee_amount, token_addr) = {
+            let mut deal = self.deals.setter(deal_id);
+            let freelancer = deal.freelancer.get();
+            require(caller == freelancer, "Auth");
+
+            let status_val = deal.status.get().to::<u8>();
+            let status = DealStatus::from_u8(status_val).expect("BadS");
+
+            let milestone_idx_usize = milestone_index.to::<usize>();
+            require(milestone_idx_usize < deal.milestones.len(), "NoMs");
+
+            let mut milestone = deal.milestones.setter(milestone_idx_usize).expect("NoMs");
+            require(!milestone.is_released.get(), "Rel");
+            require(!milestone.is_disputed.get(), "Disp");
+
+            let start = milestone.start_timestamp.get().to::<u64>();
+            let end = milestone.end_timestamp.get().to::<u64>();
+            require(start > 0, "Strm");
+
+            let outstanding = U256::from(milestone.amount.get());
+            let vested = vested_amount(outstanding, start, end, timestamp)?;
+            require(vested > U256::ZERO, "None");
+
+            // Re-base the stream on what is left so later claims vest at the same rate
+            let left = safe_sub(outstanding, vested)?;
+            if left == U256::ZERO {
+                milestone.is_released.set(true);
+            } else {
+                milestone.amount.set(U128::from(left));
+                milestone.start_timestamp.set(U64::from(timestamp));
+            }
+
+            let new_remaining = safe_sub(deal.remaining_amount.get(), vested)?;
+            let completes = new_remaining == U256::ZERO && !is_running_retainer(&deal);
+            let next = advance(status, Action::Release { completes }, role);
+            deal.remaining_amount.set(new_remaining);
+            deal.status.set(U8::from(next.as_u8()));
+
+            let fee_amount =
+                safe_mul(vested, U256::from(RELEASE_FEE_BPS))? / U256::from(BPS_DENOMINATOR);
+            let amount = safe_sub(vested, fee_amount)?;
+
+            (freelancer, amount, fee_amount, deal.token.get())
+        };
+
+        self.accrue_fee(token_addr, fee_amount)?;
+        self.transfer_out(token_addr, freelancer, amount)?;
+
+        log(
+            self.vm(),
+            StreamClaimed {
+                deal_id,
+                milestone_index,
+                freelancer,
+                amount,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Cancels a stream before it has fully vested. The freelancer is paid what has vested, as
+    /// if claimed, and the unvested rest is refunded to the client. Either party can cancel;
+    /// a stream frozen by a dispute is left to the dispute.
+    pub fn cancel_stream(&mut self, deal_id: U256, milestone_index: U256) -> Result<(), Vec<u8>> {
+        let caller = self.vm().msg_sender();
+        let timestamp = self.vm().block_timestamp();
+        self.require_not_paused();
+        let role = self.role_of(deal_id, caller, Action::CancelStream { completes: false });
+
+        let (client, freelancer, amount, fee_amount, refund, token_addr) = {
+            let mut deal = self.deals.setter(deal_id);
+            let client = deal.client.get();
+            let freelancer = deal.freelancer.get();
+
+            let status_val = deal.status.get().to::<u8>();
+            let status = DealStatus::from_u8(status_val).expect("BadS");
+
+            let milestone_idx_usize = milestone_index.to::<usize>();
+            require(milestone_idx_usize < deal.milestones.len(), "NoMs");
+
+            // Read deal-level state before the milestone setter borrows `deal`
+            let current_remaining = deal.remaining_amount.get();
+            let running_retainer = is_running_retainer(&deal);
+
+            let mut milestone = deal.milestones.setter(milestone_idx_usize).expect("NoMs");
+            require(!milestone.is_released.get(), "Rel");
+            require(!milestone.is_disputed.get(), "Disp");
+
+            let start = milestone.start_timestamp.get().to::<u64>();
+            let end = milestone.end_timestamp.get().to::<u64>();
+            require(start > 0, "Strm");
+
+            let outstanding = U256::from(milestone.amount.get());
+            let vested = vested_amount(outstanding, start, end, timestamp)?;
+            let refund = safe_sub(outstanding, vested)?;
+            require(refund > U256::ZERO, "Vstd");
+
+            let outcome = if vested == U256::ZERO {
+                MilestoneOutcome::Refund
+            } else {
+                MilestoneOutcome::Split
+            };
+            milestone.is_released.set(true);
+            milestone.outcome.set(U8::from(outcome.as_u8()));
+            milestone.client_award.set(U128::from(refund));
+
+            let new_remaining = safe_sub(current_remaining, outstanding)?;
+            let completes = new_remaining == U256::ZERO && !running_retainer;
+            let next = advance(status, Action::CancelStream { completes }, role);
+            deal.remaining_amount.set(new_remaining);
+            deal.status.set(U8::from(next.as_u8()));
+
+            let fee_amount =
+                safe_mul(vested, U256::from(RELEASE_FEE_BPS))? / U256::from(BPS_DENOMINATOR);
+            let amount = safe_sub(vested, fee_amount)?;
+
+            (
+                client,
+                freelancer,
+                amount,
+                fee_amount,
+                refund,
+                deal.token.get(),
+            )
+        };
+
+        self.accrue_fee(token_addr, fee_amount)?;
+        self.transfer_out(token_addr, freelancer, amount)?;
+        self.transfer_out(token_addr, client, refund)?;
+
+        log(
+            self.vm(),
+            StreamCancelled {
+                deal_id,
+                milestone_index,
+                cancelled_by: caller,
+                freelancer_amount: amount,
+                refund,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Retrieves a milestone's stream as (start, end, claimable). A zero start means the
+    /// milestone is paid in one lump.
+    pub fn get_stream(&self, deal_id: U256, index: U256) -> Result<(U256, U256, U256), Vec<u8>> {
+        let timestamp = self.vm().block_timestamp();
+        let deal = self.deals.get(deal_id);
+        if index >= U256::from(deal.milestones.len()) {
+            return Err(Vec::new());
+        }
+
+        let milestone = deal.milestones.get(index.to::<usize>()).unwrap();
+        let start = milestone.start_timestamp.get().to::<u64>();
+        let end = milestone.end_timestamp.get().to::<u64>();
+        let claimable = if start == 0 || milestone.is_released.get() || milestone.is_disputed.get()
+        {
+            U256::ZERO
+        } else {
+            vested_amount(U256::from(milestone.amount.get()), start, end, timestamp)?
+        };
+        Ok((U256::from(start), U256::from(end), claimable))
+    }
+
+    /// Opens a recurring retainer paying `period_amount` every `period_length` seconds, funding
+    /// the first `periods` periods up front. Each funded period becomes a milestone time-locked
+    /// until the period ends. Either party can end the retainer after `notice_periods` further
+    /// periods with `end_retainer`.
+    #[payable]
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_retainer(
+        &mut self,
+        freelancer: Address,
+        arbiter: Address,
+        token: Address,
+        period_amount: U256,
+        period_length: u64,
+        periods: u64,
+        notice_periods: u64,
+        panel: Vec<Address>,
+        fee_mode: u8,
+    ) -> Result<U256, Vec<u8>> {
+        let timestamp = self.vm().block_timestamp();
+        require(period_length > 0 && periods > 0, "Period");
+
+        let end_times = period_ends(timestamp, period_length, periods);
+        let funded_until = end_times[end_times.len() - 1];
+        let amount = safe_mul(period_amount, U256::from(periods))?;
+        let len = end_times.len();
+        let deal_id = self.create_deal_with_terms(
+            U256::ZERO,
+            freelancer,
+            arbiter,
+            token,
+            amount,
+            vec![period_amount; len],
+            end_times.into_iter().map(U256::from).collect(),
+            vec![U256::ZERO; len],
+            vec![U256::ZERO; len],
+            panel,
+            fee_mode,
+        )?;
+
+        {
+            let mut deal = self.deals.setter(deal_id);
+            deal.period_amount.set(period_amount);
+            deal.period_length.set(U64::from(period_length));
+            deal.notice_periods.set(U64::from(notice_periods));
+            deal.funded_until.set(U64::from(funded_until));
+        }
+
+        log(
+            self.vm(),
+            RetainerFunded {
+                deal_id,
+                periods,
+                funded_until,
+            },
+        );
+
+        Ok(deal_id)
+    }
+
+    /// Funds `periods` more periods of a running retainer. They follow the last funded period,
+    /// or start now if it has already ended.
+    #[payable]
+    pub fn fund_retainer(&mut self, deal_id: U256, periods: u64) -> Result<(), Vec<u8>> {
+        let caller = self.vm().msg_sender();
+        let timestamp = self.vm().block_timestamp();
+        self.require_not_paused();
+        require(periods > 0, "Period");
+        let role = self.role_of(deal_id, caller, Action::Fund);
+
+        let (token_addr, amount, funded_until) = {
+            let mut deal = self.deals.setter(deal_id);
+
+            let status_val = deal.status.get().to::<u8>();
+            let status = DealStatus::from_u8(status_val).expect("BadS");
+            advance(status, Action::Fund, role);
+            require(is_running_retainer(&deal), "NoRet");
+
+            let period_amount = deal.period_amount.get();
+            let period_length = deal.period_length.get().to::<u64>();
+            let from = deal.funded_until.get().to::<u64>().max(timestamp);
+            let end_times = period_ends(from, period_length, periods);
+            let funded_until = end_times[end_times.len() - 1];
+            for end_time in end_times {
+                let mut milestone = deal.milestones.grow();
+                milestone.amount.set(U128::from(period_amount));
+                milestone.end_timestamp.set(U64::from(end_time));
+            }
+
+            let amount = safe_mul(period_amount, U256::from(periods))?;
+            let remaining = safe_add(deal.remaining_amount.get(), amount)?;
+            deal.remaining_amount.set(remaining);
+            deal.funded_until.set(U64::from(funded_until));
+
+            (deal.token.get(), amount, funded_until)
+        };
+
+        self.collect(token_addr, caller, amount)?;
+
+        log(
+            self.vm(),
+            RetainerFunded {
+                deal_id,
+                periods,
+                funded_until,
+            },
+        );
+        Ok(())
+    }
+
+    /// Ends a retainer. The current period and `notice_periods` after it are still paid out
+    /// as usual; every later funded period is refunded to the client now. Disputed periods
+    /// are left to the dispute.
+    pub fn end_retainer(&mut self, deal_id: U256) -> Result<(), Vec<u8>> {
+        let caller = self.vm().msg_sender();
+        let timestamp = self.vm().block_timestamp();
+        self.require_not_paused();
+        let role = self.role_of(deal_id, caller, Action::EndRetainer { completes: false });
+
+        let (client, token_addr, ends_at, refund) = {
+            let mut deal = self.deals.setter(deal_id);
+            require(is_running_retainer(&deal), "NoRet");
+
+            // The notice runs from the end of the period in progress
+            let period_length = deal.period_length.get().to::<u64>();
+            let mut current_end = timestamp;
+            for i in 0..deal.milestones.len() {
+                let end = deal
+                    .milestones
+                    .get(i)
+                    .expect("NoMs")
+                    .end_timestamp
+                    .get()
+                    .to::<u64>();
+                if end > timestamp {
+                    current_end = end;
+                    break;
+                }
+            }
+            let notice = deal.notice_periods.get().to::<u64>();
+            let ends_at = current_end
+                .saturating_add(notice.saturating_mul(period_length))
+                .min(deal.funded_until.get().to::<u64>().max(timestamp));
+
+            let mut refund = U256::ZERO;
+            for i in 0..deal.milestones.len() {
+                let mut milestone = deal.milestones.setter(i).expect("NoMs");
+                let unspent = milestone.end_timestamp.get().to::<u64>() > ends_at
+                    && !milestone.is_released.get()
+                    && !milestone.is_disputed.get();
+                if unspent {
+                    let amount = milestone.amount.get();
+                    milestone.is_released.set(true);
+                    milestone
+                        .outcome
+                        .set(U8::from(MilestoneOutcome::Refund.as_u8()));
+                    milestone.client_award.set(amount);
+                    refund = safe_add(refund, U256::from(amount))?;
+                }
+            }
+
+            let remaining = safe_sub(deal.remaining_amount.get(), refund)?;
+            let status_val = deal.status.get().to::<u8>();
+            let status = DealStatus::from_u8(status_val).expect("BadS");
+            let completes = remaining == U256::ZERO;
+            let next = advance(status, Action::EndRetainer { completes }, role);
+
+            deal.remaining_amount.set(remaining);
+            deal.retainer_end.set(U64::from(ends_at));
+            deal.status.set(U8::from(next.as_u8()));
+
+            (deal.client.get(), deal.token.get(), ends_at, refund)
+        };
+
+        self.transfer_out(token_addr, client, refund)?;
+
+        log(
+            self.vm(),
+            RetainerEnded {
+                deal_id,
+                ended_by: caller,
+                ends_at,
+                refund,
+            },
+        );
+        Ok(())
+    }
+
+    /// Retrieves a deal's retainer terms as (period_amount, period_length, notice_periods,
+    /// funded_until, retainer_end). A zero period length means the deal is not a retainer.
+    pub fn get_retainer(&self, deal_id: U256) -> (U256, u64, u64, u64, u64) {
+        let deal = self.deals.get(deal_id);
+        (
+            deal.period_amount.get(),
+            deal.period_length.get().to::<u64>(),
+            deal.notice_periods.get().to::<u64>(),
+            deal.funded_until.get().to::<u64>(),
+            deal.retainer_end.get().to::<u64>(),
+        )
+    }
+
+    /// Escalates the deal into a disputed state, freezing every unreleased milestone
+    /// until the designated arbiter intervenes and resolves the conflict. Streams first pay out
+    /// what has vested. The caller posts the dispute bond configured for the deal's token.
+    #[payable]
+    pub fn raise_dispute(&mut self, deal_id: U256) -> Result<(), Vec<u8>> {
+        let milestone_count = self.deals.get(deal_id).milestones.len();
+        let mut indices = Vec::new();
+        for i in 0..milestone_count {
+            let released = self
+                .deals
+                .get(deal_id)
+                .milestones
+                .get(i)
+                .map(|m| m.is_released.get())
+                .unwrap_or(true);
+            if !released {
+                indices.push(U256::from(i));
+            }
+        }
+
+        self.open_dispute(deal_id, indices)
+    }
+
+    /// Disputes only the given milestone indices. The remaining milestones can still be
+    /// released while the dispute is open, and the deal returns to `Active` once it is resolved.
+    #[payable]
+    pub fn raise_milestone_dispute(
+        &mut self,
+        deal_id: U256,
+        milestone_indices: Vec<U256>,
+    ) -> Result<(), Vec<u8>> {
+        self.open_dispute(deal_id, milestone_indices)
+    }
+
+    /// Matches the dispute bond posted by the party that raised the dispute. Only a
+    /// counterparty that has matched the bond receives it if the initiator loses; otherwise it
+    /// goes to the arbiters. Possible until the first ruling or vote.
+    #[payable]
+    pub fn match_dispute_bond(&mut self, deal_id: U256) -> Result<(), Vec<u8>> {
+        let caller = self.vm().msg_sender();
+        self.require_not_paused();
+        let role = self.role_of(deal_id, caller, Action::MatchBond);
+
+        let (token_addr, bond) = {
+            let mut deal = self.deals.setter(deal_id);
+
+            let status_val = deal.status.get().to::<u8>();
+            let status = DealStatus::from_u8(status_val).expect("BadS");
+            advance(status, Action::MatchBond, role);
+
+            require(caller != deal.dispute_initiator.get(), "Auth");
+            require(!deal.is_resolved.get() && deal.votes.is_empty(), "Rul");
+            require(!deal.bond_matched.get(), "Dup");
+
+            let bond = deal.dispute_bond.get();
+            require(bond > U256::ZERO, "NoBond");
+            deal.bond_matched.set(true);
+
+            (deal.token.get(), bond)
+        };
+
+        self.collect(token_addr, caller, bond)?;
+
+        log(
+            self.vm(),
+            DisputeBondPosted {
+                deal_id,
+                party: caller,
+                amount: bond,
+            },
+        );
+        Ok(())
+    }
+
+    /// Retrieves who bears the deal's arbiter fee (FeeMode enum).
+    pub fn get_fee_mode(&self, deal_id: U256) -> u8 {
+        self.deals.get(deal_id).fee_mode.get().to::<u8>()
+    }
+
+    /// Retrieves the current dispute's bond and whether the counterparty has matched it.
+    pub fn get_dispute_bond(&self, deal_id: U256) -> (U256, bool) {
+        let deal = self.deals.get(deal_id);
+        (deal.dispute_bond.get(), deal.bond_matched.get())
+    }
+
+    /// Retrieves the dispute bond configured for `token` deals as (amount, bps).
+    pub fn get_dispute_bond_config(&self, token: Address) -> (U256, U256) {
+        (
+            self.dispute_bond_amounts.get(token),
+            self.dispute_bond_bps.get(token),
+        )
+    }
+
+    /// Resolves an active dispute by distributing the disputed funds between the client
+    /// and freelancer according to the arbiter's ruling, after deducting the arbiter's fee.
+    /// Any part of the disputed amount not allocated by the ruling is refunded to the client.
+    /// On panel deals each panelist submits a ruling instead; the deal settles once a majority
+    /// agrees, or on the median ruling once every panelist has voted.
+    pub fn resolve_dispute(
+        &mut self,
+        deal_id: U256,
+        client_share: U256,
+        freelancer_share: U256,
+    ) -> Result<(), Vec<u8>> {
+        let caller = self.vm().msg_sender();
+        self.require_not_paused();
+        let role = self.role_of(deal_id, caller, Action::Rule);
+
+        let (arbiter_addr, panel_size) = {
+            let deal = self.deals.get(deal_id);
+
+            let status_val = deal.status.get().to::<u8>();
+            let status = DealStatus::from_u8(status_val).expect("BadS");
+
+            advance(status, Action::Rule, role);
+            require(!deal.is_resolved.get(), "Res");
+
+            // Rulings only cover the milestones frozen by the dispute
+            let disputed_amount = deal.disputed_amount.get();
+            require(
+                safe_add(client_share, freelancer_share)? <= disputed_amount,
+                "Over",
+            );
+
+            (deal.arbiter.get(), deal.panel.len())
+        };
+
+        // Single arbiter: the ruling is final
+        if panel_size == 0 {
+            return self.issue_ruling(deal_id, client_share, freelancer_share, vec![arbiter_addr]);
+        }
+
+        // Panel: record the vote and check whether a decision has been reached
+        let (voters, decision) = {
+            let mut deal = self.deals.setter(deal_id);
+
+            let mut voters = Vec::new();
+            let mut rulings = Vec::new();
+            for i in 0..deal.votes.len() {
+                let vote = deal.votes.get(i).expect("Vote");
+                voters.push(vote.arbiter.get());
+                rulings.push((vote.client_share.get(), vote.freelancer_share.get()));
+            }
+            require(!voters.contains(&caller), "Voted");
+
+            let mut vote = deal.votes.grow();
+            vote.arbiter.set(caller);
+            vote.client_share.set(client_share);
+            vote.freelancer_share.set(freelancer_share);
+            voters.push(caller);
+            rulings.push((client_share, freelancer_share));
+
+            let majority = panel_size / 2 + 1;
+            let agreeing = rulings
+                .iter()
+                .filter(|r| **r == (client_share, freelancer_share))
+                .count();
+
+            let decision = if agreeing >= majority {
+                Some((client_share, freelancer_share))
+            } else if rulings.len() == panel_size {
+                Some(median_ruling(&mut rulings))
+            } else {
+                None
+            };
+
+            (voters, decision)
+        };
+
+        log(
+            self.vm(),
+            RulingSubmitted {
+                deal_id,
+                arbiter: caller,
+                client_share,
+                freelancer_share,
+            },
+        );
+
+        if let Some((client_share, freelancer_share)) = decision {
+            self.issue_ruling(deal_id, client_share, freelancer_share, voters)?;
+        }
+
+        Ok(())
+    }
+
+    /// Withdraws an open dispute, unfreezing its milestones and returning the deal to the
+    /// status it had before. Only the party that raised it may withdraw, and only before any
+    /// arbiter has ruled or voted on it. Both dispute bonds are refunded, and streams frozen
+    /// by the dispute resume where they were paused.
+    pub fn withdraw_dispute(&mut self, deal_id: U256) -> Result<(), Vec<u8>> {
+        let caller = self.vm().msg_sender();
+        let timestamp = self.vm().block_timestamp();
+        self.require_not_paused();
+        let resumes = {
+            let deal = self.deals.get(deal_id);
+            let pre_dispute =
+                DealStatus::from_u8(deal.pre_dispute_status.get().to::<u8>()).expect("BadS");
+
+            // Milestones released outside the dispute's scope make a funded deal active
+            let mut released = false;
+            for i in 0..deal.milestones.len() {
+                released |= deal.milestones.get(i).expect("NoMs").is_released.get();
+            }
+            if released && pre_dispute == DealStatus::Funded {
+                DealStatus::Active
+            } else {
+                pre_dispute
+            }
+        };
+        let action = Action::WithdrawDispute { resumes };
+        let role = self.role_of(deal_id, caller, action);
+        {
+            let mut deal = self.deals.setter(deal_id);
+
+            let status_val = deal.status.get().to::<u8>();
+            let status = DealStatus::from_u8(status_val).expect("BadS");
+            let next = advance(status, action, role);
+
+            require(caller == deal.dispute_initiator.get(), "Auth");
+            require(deal.appeal_deadline.get() == U64::ZERO, "Rul");
+            require(deal.votes.is_empty(), "Voted");
+
+            let disputed_at = deal.disputed_at.get().to::<u64>();
+            for i in 0..deal.milestones.len() {
+                let mut milestone = deal.milestones.setter(i).expect("NoMs");
+                if !milestone.is_disputed.get() {
+                    continue;
+                }
+                milestone.is_disputed.set(false);
+
+                // Push the rest of a paused stream back by the time it spent frozen
+                let start = milestone.start_timestamp.get().to::<u64>();
+                let end = milestone.end_timestamp.get().to::<u64>();
+                let paused_from = disputed_at.max(start);
+                if start > 0 && paused_from < end && timestamp > paused_from {
+                    let paused = timestamp - paused_from;
+                    milestone
+                        .start_timestamp
+                        .set(U64::from(start.saturating_add(paused)));
+                    milestone
+                        .end_timestamp
+                        .set(U64::from(end.saturating_add(paused)));
+                }
+            }
+
+            deal.status.set(U8::from(next.as_u8()));
+            deal.disputed_amount.set(U256::ZERO);
+        }
+
+        self.release_dispute_bonds(deal_id, Address::ZERO, &[])?;
+
+        log(
+            self.vm(),
+            DisputeWithdrawn {
+                deal_id,
+                initiator: caller,
+            },
+        );
+        Ok(())
+    }
+
+    /// Resolves a single-arbiter dispute with one outcome per disputed milestone: refund to the
+    /// client, release to the freelancer, or a split giving the client `client_amounts[i]`.
+    /// Every disputed milestone must be ruled on, and outcomes are recorded on the milestones.
+    pub fn resolve_dispute_by_milestone(
+        &mut self,
+        deal_id: U256,
+        milestone_indices: Vec<U256>,
+        outcomes: Vec<u8>,
+        client_amounts: Vec<U256>,
+    ) -> Result<(), Vec<u8>> {
+        let caller = self.vm().msg_sender();
+        self.require_not_paused();
+        let role = self.role_of(deal_id, caller, Action::Rule);
+
+        let (arbiter_addr, client_share, freelancer_share) = {
+            let mut deal = self.deals.setter(deal_id);
+
+            let status_val = deal.status.get().to::<u8>();
+            let status = DealStatus::from_u8(status_val).expect("BadS");
+
+            advance(status, Action::Rule, role);
+            require(!deal.is_resolved.get(), "Res");
+            require(deal.panel.is_empty(), "Panel");
+
+            let arbiter_addr = deal.arbiter.get();
+
+            let len = milestone_indices.len();
+            require(outcomes.len() == len, "Len");
+            require(client_amounts.len() == len, "Len");
+
+            let mut client_share = U256::ZERO;
+            let mut freelancer_share = U256::ZERO;
+            for k in 0..len {
+                let idx = milestone_indices[k].to::<usize>();
+                require(idx < deal.milestones.len(), "NoMs");
+
+                let mut milestone = deal.milestones.setter(idx).expect("NoMs");
+                require(milestone.is_disputed.get(), "NotDisp");
+                require(milestone.outcome.get().to::<u8>() == 0, "Dup");
+
+                let amount = U256::from(milestone.amount.get());
+                let client_amount = match MilestoneOutcome::from_u8(outcomes[k]) {
+                    Some(MilestoneOutcome::Refund) => amount,
+                    Some(MilestoneOutcome::Release) => U256::ZERO,
+                    Some(MilestoneOutcome::Split) => {
+                        require(client_amounts[k] <= amount, "Over");
+                        client_amounts[k]
+                    }
+                    _ => panic!("BadO"),
+                };
+
+                milestone.outcome.set(U8::from(outcomes[k]));
+                milestone.client_award.set(U128::from(client_amount));
+
+                client_share = safe_add(client_share, client_amount)?;
+                freelancer_share = safe_add(freelancer_share, safe_sub(amount, client_amount)?)?;
+            }
+
+            // Every disputed milestone must carry an outcome
+            for i in 0..deal.milestones.len() {
+                let milestone = deal.milestones.get(i).expect("NoMs");
+                if milestone.is_disputed.get() {
+                    require(milestone.outcome.get().to::<u8>() != 0, "Cover");
+                }
+            }
+
+            (arbiter_addr, client_share, freelancer_share)
+        };
+
+        for k in 0..milestone_indices.len() {
+            log(
+                self.vm(),
+                MilestoneRuled {
+                    deal_id,
+                    milestone_index: milestone_indices[k],
+                    outcome: outcomes[k],
+                    client_amount: self
+                        .deals
+                        .get(deal_id)
+                        .milestones
+                        .get(milestone_indices[k].to::<usize>())
+                        .map(|m| U256::from(m.client_award.get()))
+                        .unwrap_or(U256::ZERO),
+                },
+            );
+        }
+
+        self.issue_ruling(deal_id, client_share, freelancer_share, vec![arbiter_addr])
+    }
+
+    /// Appeals a pending ruling to the appeal court by posting the appeal bond in the deal's
+    /// token. Only possible while the appeal window is open.
+    #[payable]
+    pub fn appeal(&mut self, deal_id: U256) -> Result<(), Vec<u8>> {
+        let caller = self.vm().msg_sender();
+        let timestamp = self.vm().block_timestamp();
+        let bond_bps = self.appeal_bond_bps.get();
+        self.require_not_paused();
+        let role = self.role_of(deal_id, caller, Action::Appeal);
+
+        let (token_addr, bond) = {
+            let mut deal = self.deals.setter(deal_id);
+
+            let status_val = deal.status.get().to::<u8>();
+            let status = DealStatus::from_u8(status_val).expect("BadS");
+            advance(status, Action::Appeal, role);
+
+            let deadline = deal.appeal_deadline.get().to::<u64>();
+            require(deadline > 0, "NoRul");
+            require(timestamp < deadline, "Time");
+            require(deal.appellant.get() == Address::ZERO, "Appd");
+
+            // From here on the deadline is the court's
+            deal.appeal_deadline
+                .set(U64::from(timestamp + APPEAL_RULING_WINDOW));
+
+            let ruled = safe_add(
+                deal.pending_client_share.get(),
+                deal.pending_freelancer_share.get(),
+            )?;
+            let bond = safe_mul(ruled, bond_bps)? / U256::from(BPS_DENOMINATOR);
+
+            deal.appellant.set(caller);
+            deal.appeal_bond.set(bond);
+
+            (deal.token.get(), bond)
+        };
+
+        self.collect(token_addr, caller, bond)?;
+
+        log(
+            self.vm(),
+            RulingAppealed {
+                deal_id,
+                appellant: caller,
+                bond,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Rules on an appealed dispute before the court's deadline. The appeal bond is refunded
+    /// if the appellant's share improves on the original ruling, otherwise it is forfeited to
+    /// the counterparty. The court takes `APPEAL_COURT_FEE_BPS` of the arbiter fee and the
+    /// first-round arbiters split the rest; their track records are credited only if the
+    /// court upholds their ruling.
+    pub fn resolve_appeal(
+        &mut self,
+        deal_id: U256,
+        client_share: U256,
+        freelancer_share: U256,
+    ) -> Result<(), Vec<u8>> {
+        let caller = self.vm().msg_sender();
+        let timestamp = self.vm().block_timestamp();
+        self.require_not_paused();
+        let role = self.role_of(deal_id, caller, Action::RuleAppeal);
+
+        let (token_addr, bond_recipient, bond, upheld_appeal, arbiters) = {
+            let mut deal = self.deals.setter(deal_id);
+
+            let status_val = deal.status.get().to::<u8>();
+            let status = DealStatus::from_u8(status_val).expect("BadS");
+            advance(status, Action::RuleAppeal, role);
+
+            let appellant = deal.appellant.get();
+            require(appellant != Address::ZERO, "NotAppd");
+            require(timestamp < deal.appeal_deadline.get().to::<u64>(), "Late");
+            require(
+                safe_add(client_share, freelancer_share)? <= deal.disputed_amount.get(),
+                "Over",
+            );
+
+            let client = deal.client.get();
+            let freelancer = deal.freelancer.get();
+            let upheld_appeal = if appellant == client {
+                client_share > deal.pending_client_share.get()
+            } else {
+                freelancer_share > deal.pending_freelancer_share.get()
+            };
+            let bond_recipient = if upheld_appeal {
+                appellant
+            } else if appellant == client {
+                freelancer
+            } else {
+                client
+            };
+
+            let bond = deal.appeal_bond.get();
+            deal.appeal_bond.set(U256::ZERO);
+            deal.appeal_deadline.set(U64::ZERO);
+            deal.appellant.set(Address::ZERO);
+
+            let mut arbiters = Vec::new();
+            for i in 0..deal.ruling_arbiters.len() {
+                arbiters.push(deal.ruling_arbiters.get(i).unwrap_or(Address::ZERO));
+            }
+
+            // The court's aggregate ruling replaces any per-milestone outcomes
+            for i in 0..deal.milestones.len() {
+                let mut milestone = deal.milestones.setter(i).expect("NoMs");
+                if milestone.is_disputed.get() {
+                    milestone
+                        .outcome
+                        .set(U8::from(MilestoneOutcome::None.as_u8()));
+                    milestone.client_award.set(U128::ZERO);
+                }
+            }
+
+            (
+                deal.token.get(),
+                bond_recipient,
+                bond,
+                upheld_appeal,
+                arbiters,
+            )
+        };
+
+        self.transfer_out(token_addr, bond_recipient, bond)?;
+        self.settle_dispute(
+            deal_id,
+            client_share,
+            freelancer_share,
+            arbiters,
+            caller,
+            !upheld_appeal,
+        )
+    }
+
+    /// Executes a pending ruling once its appeal window has closed without an appeal, or once
+    /// the appeal court has let its deadline pass. In the latter case the original ruling
+    /// stands and the appeal bond is refunded.
+    pub fn execute_ruling(&mut self, deal_id: U256) -> Result<(), Vec<u8>> {
+        let timestamp = self.vm().block_timestamp();
+        self.require_not_paused();
+
+        let (client_share, freelancer_share, arbiters, token_addr, appellant, bond) = {
+            let mut deal = self.deals.setter(deal_id);
+
+            let deadline = deal.appeal_deadline.get().to::<u64>();
+            require(deadline > 0, "NoRul");
+            require(timestamp >= deadline, "Time");
+
+            let appellant = deal.appellant.get();
+            let bond = deal.appeal_bond.get();
+            deal.appellant.set(Address::ZERO);
+            deal.appeal_bond.set(U256::ZERO);
+
+            let mut arbiters = Vec::new();
+            for i in 0..deal.ruling_arbiters.len() {
+                arbiters.push(deal.ruling_arbiters.get(i).unwrap_or(Address::ZERO));
+            }
+            deal.appeal_deadline.set(U64::ZERO);
+
+            (
+                deal.pending_client_share.get(),
+                deal.pending_freelancer_share.get(),
+                arbiters,
+                deal.token.get(),
+                appellant,
+                bond,
+            )
+        };
+
+        self.transfer_out(token_addr, appellant, bond)?;
+        self.settle_dispute(
+            deal_id,
+            client_share,
+            freelancer_share,
+            arbiters,
+            Address::ZERO,
+            true,
+        )
+    }
+
+    /// Retrieves the ruling awaiting execution as (client_share, freelancer_share,
+    /// appeal_deadline, appellant). A zero deadline means no ruling is pending; once the
+    /// ruling is appealed, the deadline is the appeal court's.
+    pub fn get_pending_ruling(&self, deal_id: U256) -> (U256, U256, U256, Address) {
+        let deal = self.deals.get(deal_id);
+        (
+            deal.pending_client_share.get(),
+            deal.pending_freelancer_share.get(),
+            U256::from(deal.appeal_deadline.get()),
+            deal.appellant.get(),
+        )
+    }
+
+    /// Offers to close the deal by splitting everything still in escrow, disputed or not,
+    /// between the parties. Either party may propose, replacing any open offer; the deal
+    /// settles once the counterparty accepts. Not possible while a ruling is pending.
+    pub fn propose_settlement(
+        &mut self,
+        deal_id: U256,
+        client_share: U256,
+        freelancer_share: U256,
+    ) -> Result<(), Vec<u8>> {
+        let caller = self.vm().msg_sender();
+        let role = self.role_of(deal_id, caller, Action::ProposeSettlement);
+        {
+            let mut deal = self.deals.setter(deal_id);
+
+            let status_val = deal.status.get().to::<u8>();
+            let status = DealStatus::from_u8(status_val).expect("BadS");
+            advance(status, Action::ProposeSettlement, role);
+
+            require(deal.appeal_deadline.get() == U64::ZERO, "Rul");
+            require(
+                safe_add(client_share, freelancer_share)? == deal.remaining_amount.get(),
+                "Sum",
+            );
+
+            deal.settlement_proposer.set(caller);
+            deal.settlement_client_share.set(client_share);
+            deal.settlement_freelancer_share.set(freelancer_share);
+        }
+
+        log(
+            self.vm(),
+            SettlementProposed {
+                deal_id,
+                proposer: caller,
+                client_share,
+                freelancer_share,
+            },
+        );
+        Ok(())
+    }
+
+    /// Accepts the counterparty's open settlement offer and pays it out, completing the deal.
+    /// The shares are repeated so the proposer cannot swap the offer before acceptance. No
+    /// arbiter fee applies; the freelancer's share carries the usual release fee.
+    pub fn accept_settlement(
+        &mut self,
+        deal_id: U256,
+        client_share: U256,
+        freelancer_share: U256,
+    ) -> Result<(), Vec<u8>> {
+        let caller = self.vm().msg_sender();
+        self.require_not_paused();
+        let role = self.role_of(deal_id, caller, Action::AcceptSettlement);
+
+        let (client, freelancer, token_addr) = {
+            let mut deal = self.deals.setter(deal_id);
+
+            let status_val = deal.status.get().to::<u8>();
+            let status = DealStatus::from_u8(status_val).expect("BadS");
+            let next = advance(status, Action::AcceptSettlement, role);
+
+            let proposer = deal.settlement_proposer.get();
+            require(proposer != Address::ZERO, "NoOffer");
+            require(proposer != caller, "Auth");
+            require(
+                deal.settlement_client_share.get() == client_share
+                    && deal.settlement_freelancer_share.get() == freelancer_share,
+                "Offer",
+            );
+            require(deal.appeal_deadline.get() == U64::ZERO, "Rul");
+            // Releases since the offer was made invalidate it
+            require(
+                safe_add(client_share, freelancer_share)? == deal.remaining_amount.get(),
+                "Sum",
+            );
+
+            for i in 0..deal.milestones.len() {
+                let mut milestone = deal.milestones.setter(i).expect("NoMs");
+                milestone.is_disputed.set(false);
+                milestone.is_released.set(true);
+            }
+            deal.remaining_amount.set(U256::ZERO);
+            deal.disputed_amount.set(U256::ZERO);
+            deal.votes.erase();
+            deal.settlement_proposer.set(Address::ZERO);
+            deal.settlement_client_share.set(U256::ZERO);
+            deal.settlement_freelancer_share.set(U256::ZERO);
+            deal.status.set(U8::from(next.as_u8()));
+
+            (deal.client.get(), deal.freelancer.get(), deal.token.get())
+        };
+
+        let fee =
+            safe_mul(freelancer_share, U256::from(RELEASE_FEE_BPS))? / U256::from(BPS_DENOMINATOR);
+        let net_freelancer = safe_sub(freelancer_share, fee)?;
+
+        self.accrue_fee(token_addr, fee)?;
+        self.transfer_out(token_addr, client, client_share)?;
+        self.transfer_out(token_addr, freelancer, net_freelancer)?;
+        // Settling an open dispute refunds its bonds
+        self.release_dispute_bonds(deal_id, Address::ZERO, &[])?;
+
+        log(
+            self.vm(),
+            SettlementAccepted {
+                deal_id,
+                client_amount: client_share,
+                freelancer_amount: net_freelancer,
+                fee,
+            },
+        );
+        Ok(())
+    }
+
+    /// Retrieves the open settlement offer as (proposer, client_share, freelancer_share).
+    /// A zero proposer means no offer is open.
+    pub fn get_settlement(&self, deal_id: U256) -> (Address, U256, U256) {
+        let deal = self.deals.get(deal_id);
+        (
+            deal.settlement_proposer.get(),
+            deal.settlement_client_share.get(),
+            deal.settlement_freelancer_share.get(),
+        )
+    }
+
+    /// Proposes replacing `old_arbiter` (the deal's arbiter or one of its panelists) with
+    /// `new_arbiter`. The reassignment takes effect once the counterparty proposes the same swap.
+    pub fn propose_arbiter(&mut self, deal_id: U256, old_arbiter: Address, new_arbiter: Address) {
+        let caller = self.vm().msg_sender();
+        self.require_reassignable(deal_id, Action::ReassignArbiter, old_arbiter, new_arbiter);
+
+        let agreed = {
+            let mut deal = self.deals.setter(deal_id);
+
+            let proposer = deal.arbiter_proposer.get();
+            let agreed = proposer != Address::ZERO
+                && proposer != caller
+                && deal.replaced_arbiter.get() == old_arbiter
+                && deal.proposed_arbiter.get() == new_arbiter;
+
+            if !agreed {
+                deal.replaced_arbiter.set(old_arbiter);
+                deal.proposed_arbiter.set(new_arbiter);
+                deal.arbiter_proposer.set(caller);
+            }
+            agreed
+        };
+
+        if agreed {
+            self.reassign_arbiter(deal_id, old_arbiter, new_arbiter);
+        }
+    }
+
+    /// Allows the assigned arbiter, or a panelist who has not yet voted, to step down from
+    /// the deal. The parties must then agree on a replacement with `propose_arbiter`, or fill
+    /// the seat from the registry by the commit-reveal draw.
+    pub fn recuse(&mut self, deal_id: U256) {
+        let caller = self.vm().msg_sender();
+        self.require_reassignable(deal_id, Action::Recuse, caller, Address::ZERO);
+
+        self.reassign_arbiter(deal_id, caller, Address::ZERO);
+    }
+
+    /// Vacates the seat of an arbiter the registry reports as inactive. Callable by either
+    /// party once an ArbiterRegistry is configured. Like a recused arbiter's seat, it is then
+    /// filled by agreement or by the commit-reveal draw, so neither party picks the arbiter alone.
+    pub fn replace_inactive_arbiter(&mut self, deal_id: U256, old_arbiter: Address) {
+        let registry_addr = self.arbiter_registry.get();
+        require(registry_addr != Address::ZERO, "NoReg");
+        require(old_arbiter != Address::ZERO, "0Arb");
+        self.require_reassignable(deal_id, Action::ReassignArbiter, old_arbiter, Address::ZERO);
+
+        let call = IArbiterRegistry::getArbiterStatusCall {
+            arbiter: old_arbiter,
+        };
+        match self.static_call_contract(registry_addr, call) {
+            Ok(status) => require(!status._0, "Active"),
+            Err(_) => panic!("Reg"),
+        }
+
+        self.reassign_arbiter(deal_id, old_arbiter, Address::ZERO);
+    }
+
+    /// Commits to a secret used to draw an arbiter from the registry for the deal's vacant seat.
+    /// The commitment is `arbiter_seed_commitment(secret, caller)`.
+    pub fn commit_arbiter_seed(&mut self, deal_id: U256, commitment: B256) {
+        let caller = self.vm().msg_sender();
+        let timestamp = self.vm().block_timestamp();
+        require(self.arbiter_registry.get() != Address::ZERO, "NoReg");
+        require(commitment != B256::ZERO, "0Com");
+        let role = self.role_of(deal_id, caller, Action::DrawArbiter);
+
+        let mut deal = self.deals.setter(deal_id);
+
+        let status_val = deal.status.get().to::<u8>();
+        let status = DealStatus::from_u8(status_val).expect("BadS");
+        advance(status, Action::DrawArbiter, role);
+        require(has_vacant_seat(&deal), "Assigned");
+
+        if caller == deal.client.get() {
+            require(deal.client_seed_commit.get() == B256::ZERO, "Com");
+            deal.client_seed_commit.set(commitment);
+        } else {
+            require(deal.freelancer_seed_commit.get() == B256::ZERO, "Com");
+            deal.freelancer_seed_commit.set(commitment);
+        }
+
+        // The first commitment opens the round
+        if deal.seed_deadline.get() == U64::ZERO {
+            let deadline = timestamp + SEED_REVEAL_WINDOW;
+            deal.seed_deadline.set(U64::from(deadline));
+        }
+    }
+
+    /// Reveals the caller's committed secret before the round's deadline. Once both parties
+    /// have revealed, an arbiter is drawn from the registry, weighted by stake, for the vacant seat.
+    pub fn reveal_arbiter_seed(&mut self, deal_id: U256, secret: U256) {
+        let caller = self.vm().msg_sender();
+        let timestamp = self.vm().block_timestamp();
+        let role = self.role_of(deal_id, caller, Action::DrawArbiter);
+
+        let ready = {
+            let mut deal = self.deals.setter(deal_id);
+
+            let status_val = deal.status.get().to::<u8>();
+            let status = DealStatus::from_u8(status_val).expect("BadS");
+            advance(status, Action::DrawArbiter, role);
+            require(has_vacant_seat(&deal), "Assigned");
+
+            let client_commit = deal.client_seed_commit.get();
+            let freelancer_commit = deal.freelancer_seed_commit.get();
+            require(
+                client_commit != B256::ZERO && freelancer_commit != B256::ZERO,
+                "NoCom",
+            );
+            require(timestamp <= deal.seed_deadline.get().to::<u64>(), "Late");
+
+            let commitment = arbiter_seed_commitment(secret, caller);
+            if caller == deal.client.get() {
+                require(!deal.client_seed_revealed.get(), "Rev");
+                require(commitment == client_commit, "Com");
+                deal.client_seed_revealed.set(true);
+            } else {
+                require(!deal.freelancer_seed_revealed.get(), "Rev");
+                require(commitment == freelancer_commit, "Com");
+                deal.freelancer_seed_revealed.set(true);
+            }
+
+            let seed = deal.arbiter_seed.get();
+            deal.arbiter_seed.set(seed ^ secret);
+
+            deal.client_seed_revealed.get() && deal.freelancer_seed_revealed.get()
+        };
+
+        if ready {
+            self.select_random_arbiter(deal_id);
+        }
+    }
+
+    /// Concludes a commit-reveal round that stalled past its deadline. If one party revealed,
+    /// the arbiter is drawn from their secret mixed with block data, so the party who withheld
+    /// theirs cannot steer the draw. If neither did, the round is reset for fresh commitments.
+    pub fn conclude_arbiter_draw(&mut self, deal_id: U256) {
+        let caller = self.vm().msg_sender();
+        let timestamp = self.vm().block_timestamp();
+        let block_number = self.vm().block_number();
+        let role = self.role_of(deal_id, caller, Action::DrawArbiter);
+
+        let revealed = {
+            let mut deal = self.deals.setter(deal_id);
+
+            let status_val = deal.status.get().to::<u8>();
+            let status = DealStatus::from_u8(status_val).expect("BadS");
+            advance(status, Action::DrawArbiter, role);
+            require(has_vacant_seat(&deal), "Assigned");
+
+            let deadline = deal.seed_deadline.get().to::<u64>();
+            require(deadline != 0 && timestamp > deadline, "Open");
+
+            let revealed = deal.client_seed_revealed.get() || deal.freelancer_seed_revealed.get();
+            if revealed {
+                let mut data = deal.arbiter_seed.get().to_be_bytes::<32>().to_vec();
+                data.extend_from_slice(&block_number.to_be_bytes());
+                data.extend_from_slice(&timestamp.to_be_bytes());
+                deal.arbiter_seed.set(U256::from_be_bytes(keccak(&data).0));
+            } else {
+                deal.client_seed_commit.set(B256::ZERO);
+                deal.freelancer_seed_commit.set(B256::ZERO);
+                deal.seed_deadline.set(U64::ZERO);
+            }
+            revealed
+        };
+
+        if revealed {
+            self.select_random_arbiter(deal_id);
+        }
+    }
+
+    /// Retrieves the pending reassignment proposal (replaced arbiter, proposed arbiter, proposer)
+    /// for the specified deal.
+    pub fn get_arbiter_proposal(&self, deal_id: U256) -> (Address, Address, Address) {
+        let deal = self.deals.get(deal_id);
+        (
+            deal.replaced_arbiter.get(),
+            deal.proposed_arbiter.get(),
+            deal.arbiter_proposer.get(),
+        )
+    }
+
+    /// Retrieves the deadline of the deal's open commit-reveal round (0 if none).
+    pub fn get_seed_deadline(&self, deal_id: U256) -> u64 {
+        self.deals.get(deal_id).seed_deadline.get().to::<u64>()
+    }
+
+    /// Retrieves the arbiter panel of the specified deal (empty for single-arbiter deals).
+    pub fn get_deal_panel(&self, deal_id: U256) -> Vec<Address> {
+        let deal = self.deals.get(deal_id);
+        let mut panel = Vec::new();
+        for i in 0..deal.panel.len() {
+            panel.push(deal.panel.get(i).unwrap_or(Address::ZERO));
+        }
+        panel
+    }
+
+    /// Retrieves the panel ruling at the given index as (arbiter, client_share, freelancer_share).
+    pub fn get_panel_vote(
+        &self,
+        deal_id: U256,
+        index: U256,
+    ) -> Result<(Address, U256, U256), Vec<u8>> {
+        let deal = self.deals.get(deal_id);
+        if index >= U256::from(deal.votes.len()) {
+            return Err(Vec::new());
+        }
+
+        let vote = deal.votes.get(index.to::<usize>()).unwrap();
+        Ok((
+            vote.arbiter.get(),
+            vote.client_share.get(),
+            vote.freelancer_share.get(),
+        ))
+    }
+
+    /// Retrieves detailed status information regarding a specific milestone within a deal.
+    pub fn get_milestone(
+        &self,
+        deal_id: U256,
+        index: U256,
+    ) -> Result<(U256, bool, U256, bool), Vec<u8>> {
+        let deal = self.deals.get(deal_id);
+        if index >= U256::from(deal.milestones.len()) {
+            return Err(Vec::new()); // Index out of bounds
+        }
+
+        let milestone_accessor = deal.milestones.get(index.to::<usize>());
+        if milestone_accessor.is_none() {
+            return Err(Vec::new());
+        }
+        let milestone = milestone_accessor.unwrap();
+
+        Ok((
+            U256::from(milestone.amount.get()),
+            milestone.is_released.get(),
+            U256::from(milestone.end_timestamp.get()),
+            milestone.requires_approval.get(),
+        ))
+    }
+
+    /// Retrieves whether the specified milestone is frozen by the current dispute.
+    pub fn is_milestone_disputed(&self, deal_id: U256, index: U256) -> bool {
+        let deal = self.deals.get(deal_id);
+        if index >= U256::from(deal.milestones.len()) {
+            return false;
+        }
+        deal.milestones
+            .get(index.to::<usize>())
+            .map(|m| m.is_disputed.get())
+            .unwrap_or(false)
+    }
+
+    /// Retrieves the per-milestone ruling (MilestoneOutcome) and the amount it awarded to the client.
+    pub fn get_milestone_outcome(&self, deal_id: U256, index: U256) -> Result<(u8, U256), Vec<u8>> {
+        let deal = self.deals.get(deal_id);
+        if index >= U256::from(deal.milestones.len()) {
+            return Err(Vec::new());
+        }
+
+        let milestone = deal.milestones.get(index.to::<usize>()).unwrap();
+        Ok((
+            milestone.outcome.get().to::<u8>(),
+            U256::from(milestone.client_award.get()),
+        ))
+    }
+
+    /// Retrieves the amount frozen by the current dispute of the specified deal.
+    pub fn get_disputed_amount(&self, deal_id: U256) -> U256 {
+        self.deals.get(deal_id).disputed_amount.get()
+    }
+
+    /// Retrieves the client (buyer) address associated with the specified deal.
+    pub fn get_deal_client(&self, deal_id: U256) -> Address {
+        self.deals.get(deal_id).client.get()
+    }
+
+    /// Retrieves the freelancer (seller) address associated with the specified deal.
+    pub fn get_deal_freelancer(&self, deal_id: U256) -> Address {
+        self.deals.get(deal_id).freelancer.get()
+    }
+
+    /// Retrieves the current execution status of the specified deal.
+    pub fn get_deal_status(&self, deal_id: U256) -> U256 {
+        U256::from(self.deals.get(deal_id).status.get().to::<u8>())
+    }
+
+    /// Retrieves the remaining unreleased funds allocated to the specified deal.
+    pub fn get_deal_amount(&self, deal_id: U256) -> U256 {
+        self.deals.get(deal_id).remaining_amount.get()
+    }
+
+    /// Retrieves the designated arbiter address assigned to oversee the specified deal.
+    pub fn get_deal_arbiter(&self, deal_id: U256) -> Address {
+        self.deals.get(deal_id).arbiter.get()
+    }
+}
+
+// ============================================================================
+// Helper Functions (Internal)
+// ============================================================================
+
+impl ArbiSecure {
+    fn init(&mut self, admin: Address) {
+        require(admin != Address::ZERO, "Zero");
+
+        self.admin.set(admin);
+        self.deal_counter.set(U256::ZERO);
+        self.storage_version.set(U64::from(STORAGE_VERSION));
+        self.timelock_delay.set(U64::from(DEFAULT_TIMELOCK_DELAY));
+        for role in OPERATIONAL_ROLES {
+            self.set_role(role, admin, true);
+        }
+
+        log(
+            self.vm(),
+            Initialized {
+                admin,
+                storage_version: STORAGE_VERSION,
+            },
+        );
+    }
 
-            // Update status
-            if new_remaining == U256::ZERO {
-                deal.status.set(U8::from(DealStatus::Completed.as_u8()));
-            } else {
-                deal.status.set(U8::from(DealStatus::Active.as_u8()));
+    /// Lists every ERC20 token an existing deal is funded in.
+    fn list_deal_tokens(&mut self) {
+        let deal_count = self.deal_counter.get().to::<u64>();
+        for deal_id in 0..deal_count {
+            let token = self.deals.get(U256::from(deal_id)).token.get();
+            if token == Address::ZERO || self.allowed_tokens.get(token) {
+                continue;
             }
+            self.allowed_tokens.setter(token).set(true);
+            log(
+                self.vm(),
+                TokenListed {
+                    token,
+                    allowed: true,
+                },
+            );
+        }
+    }
 
-            let token_addr = deal.token.get();
+    /// Queues a privileged operation after checking the caller may schedule it.
+    fn schedule(&mut self, kind: OperationKind, params: Vec<u8>) -> Result<U256, Vec<u8>> {
+        self.only_role(kind.role());
 
-            // Fee (0.5%)
-            let fee_bps = U256::from(50);
-            let fee_amount = milestone_amount * fee_bps / U256::from(10000);
-            let amount = milestone_amount - fee_amount;
+        let timestamp = self.vm().block_timestamp();
+        let eta = safe_add(U256::from(timestamp), U256::from(self.timelock_delay.get()))?;
+        require(eta <= U256::from(u64::MAX), "Time");
+        let eta = eta.to::<u64>();
 
-            (freelancer, amount, token_addr)
-        };
+        let operation_id = self.operation_counter.get();
+        self.operation_counter
+            .set(safe_add(operation_id, U256::from(1))?);
 
-        if token_addr == Address::ZERO {
-            // ETH Transfer
-            let _ = self.vm().transfer_eth(freelancer, amount);
-        } else {
-            let token = IERC20::new(token_addr);
-            let result = token.transfer(&mut *self, freelancer, amount);
-            match result {
-                Ok(success) => require(success, "TokF"),
-                Err(_) => panic!("TokF"),
-            }
-        }
+        let mut operation = self.operations.setter(operation_id);
+        operation.kind.set(U8::from(kind.as_u8()));
+        operation.eta.set(U64::from(eta));
+        operation.params.set_bytes(&params);
 
         log(
             self.vm(),
-            MilestoneReleased {
-                deal_id,
-                milestone_index,
-                freelancer,
-                amount,
+            OperationScheduled {
+                operation_id,
+                kind: kind.as_u8(),
+                params: params.into(),
+                eta,
             },
         );
+        Ok(operation_id)
     }
 
-    /// Escalates the deal into a disputed state, freezing further milestone releases
-    /// until the designated arbiter intervenes and resolves the conflict.
-    pub fn raise_dispute(&mut self, deal_id: U256) {
-        let caller = self.vm().msg_sender();
-        let mut deal = self.deals.setter(deal_id);
+    /// Reads a queued operation's kind and ETA (0 if not queued).
+    fn queued(&self, operation_id: U256) -> (OperationKind, u64) {
+        let operation = self.operations.get(operation_id);
+        let kind = OperationKind::from_u8(operation.kind.get().to::<u8>()).expect("Kind");
+        (kind, operation.eta.get().to::<u64>())
+    }
 
-        let status_val = deal.status.get().to::<u8>();
-        let status = DealStatus::from_u8(status_val).expect("BadS");
+    fn clear_operation(&mut self, operation_id: U256) {
+        let mut operation = self.operations.setter(operation_id);
+        operation.kind.set(U8::ZERO);
+        operation.eta.set(U64::ZERO);
+        operation.params.erase();
+    }
 
-        require(
-            status == DealStatus::Funded || status == DealStatus::Active,
-            "BadSt",
-        );
+    /// Points the proxy at `implementation` and delegate-calls `data` on it if non-empty.
+    fn upgrade(&mut self, implementation: Address, data: &[u8]) -> Result<(), Vec<u8>> {
+        let slot = U256::from_be_bytes(IMPLEMENTATION_SLOT.0);
+        unsafe {
+            self.vm()
+                .storage_cache_bytes32(slot, implementation.into_word());
+        }
+        self.vm().flush_cache(false);
 
-        let client = deal.client.get();
-        let freelancer = deal.freelancer.get();
+        log(self.vm(), Upgraded { implementation });
 
-        require(caller == client || caller == freelancer, "Auth");
+        if !data.is_empty() {
+            unsafe { self.vm().delegate_call(&self, implementation, data) }?;
+        }
+        Ok(())
+    }
 
-        // Update deal status
-        deal.status.set(U8::from(DealStatus::Disputed.as_u8()));
+    /// Reverts unless the caller holds `role`.
+    fn only_role(&self, role: B256) {
+        require(self.has_role(role, self.vm().msg_sender()), "Role");
+    }
 
-        // We do NOT store reason/cid in storage to save space. We do not emit them either to save size.
-        // Frontend should log reason separately or emit an event from a helper contract if needed.
-        // For Core contract, we only care that it IS disputed.
-        // Update flattened dispute flag
-        deal.is_resolved.set(false);
-        deal.ruling.set(U8::from(0));
+    fn require_not_paused(&self) {
+        require(!self.paused.get(), "Paused");
+    }
+
+    /// Records a role change, emitting an event only when membership actually changes.
+    fn set_role(&mut self, role: B256, account: Address, granted: bool) {
+        if self.roles.getter(role).get(account) == granted {
+            return;
+        }
+        self.roles.setter(role).setter(account).set(granted);
+
+        let sender = self.vm().msg_sender();
+        if granted {
+            log(
+                self.vm(),
+                RoleGranted {
+                    role,
+                    account,
+                    sender,
+                },
+            );
+        } else {
+            log(
+                self.vm(),
+                RoleRevoked {
+                    role,
+                    account,
+                    sender,
+                },
+            );
+        }
+    }
+
+    /// Classifies `account`'s relationship to the deal for the state machine. An account
+    /// holding several roles (e.g. a client arbitrating their own deal) acts in the first
+    /// one that permits `action`.
+    fn role_of(&self, deal_id: U256, account: Address, action: Action) -> Role {
+        let deal = self.deals.get(deal_id);
+
+        let mut roles = Vec::new();
+        if account == deal.client.get() {
+            roles.push(Role::Client);
+        }
+        if account == deal.freelancer.get() {
+            roles.push(Role::Freelancer);
+        }
+        let mut is_arbiter = account == deal.arbiter.get();
+        for i in 0..deal.panel.len() {
+            is_arbiter |= deal.panel.get(i) == Some(account);
+        }
+        if is_arbiter {
+            roles.push(Role::Arbiter);
+        }
+        if account == self.appeal_court.get() {
+            roles.push(Role::Court);
+        }
+
+        roles
+            .into_iter()
+            .find(|role| action.permits(*role))
+            .unwrap_or(Role::Other)
+    }
+
+    /// Freezes the given unreleased milestones, collects the dispute bond from the caller and
+    /// moves the deal into `Disputed`.
+    fn open_dispute(&mut self, deal_id: U256, milestone_indices: Vec<U256>) -> Result<(), Vec<u8>> {
+        let caller = self.vm().msg_sender();
+        let timestamp = self.vm().block_timestamp();
+        self.require_not_paused();
+        let role = self.role_of(deal_id, caller, Action::RaiseDispute);
+        let token_addr = self.deals.get(deal_id).token.get();
+        let bond_amount = self.dispute_bond_amounts.get(token_addr);
+        let bond_bps = self.dispute_bond_bps.get(token_addr);
+        let (bond, freelancer, streamed) = {
+            let mut deal = self.deals.setter(deal_id);
+
+            let status_val = deal.status.get().to::<u8>();
+            let status = DealStatus::from_u8(status_val).expect("BadS");
+            let next = advance(status, Action::RaiseDispute, role);
+
+            require(!milestone_indices.is_empty(), "NoMs");
+
+            // Freeze the disputed milestones. What a stream has vested already belongs to the
+            // freelancer: it is paid out as if claimed, and only the unvested rest is frozen.
+            let mut disputed_amount = U256::ZERO;
+            let mut streamed = Vec::new();
+            let mut streamed_total = U256::ZERO;
+            for index in milestone_indices.iter() {
+                let idx = index.to::<usize>();
+                require(idx < deal.milestones.len(), "NoMs");
+
+                let mut milestone = deal.milestones.setter(idx).expect("NoMs");
+                require(!milestone.is_released.get(), "Rel");
+                require(!milestone.is_disputed.get(), "Dup");
+
+                let mut amount = U256::from(milestone.amount.get());
+                let start = milestone.start_timestamp.get().to::<u64>();
+                if start > 0 {
+                    let end = milestone.end_timestamp.get().to::<u64>();
+                    let vested = vested_amount(amount, start, end, timestamp)?;
+                    if vested > U256::ZERO {
+                        streamed.push((*index, vested));
+                        streamed_total = safe_add(streamed_total, vested)?;
+                        amount = safe_sub(amount, vested)?;
+                        if amount == U256::ZERO {
+                            milestone.is_released.set(true);
+                            continue;
+                        }
+                        milestone.amount.set(U128::from(amount));
+                        milestone.start_timestamp.set(U64::from(timestamp));
+                    }
+                }
+
+                milestone.is_disputed.set(true);
+                disputed_amount = safe_add(disputed_amount, amount)?;
+            }
+            require(disputed_amount > U256::ZERO, "None");
+            let remaining = safe_sub(deal.remaining_amount.get(), streamed_total)?;
+            deal.remaining_amount.set(remaining);
+
+            // Update deal status
+            deal.status.set(U8::from(next.as_u8()));
+            deal.disputed_amount.set(disputed_amount);
+            deal.dispute_initiator.set(caller);
+            deal.pre_dispute_status.set(U8::from(status.as_u8()));
+            deal.disputed_at.set(U64::from(timestamp));
+
+            let bond = safe_add(
+                bond_amount,
+                safe_mul(deal.remaining_amount.get(), bond_bps)? / U256::from(BPS_DENOMINATOR),
+            )?;
+            deal.dispute_bond.set(bond);
+            deal.bond_matched.set(false);
+
+            // We do NOT store reason/cid in storage to save space. We do not emit them either to save size.
+            // Frontend should log reason separately or emit an event from a helper contract if needed.
+            // For Core contract, we only care that it IS disputed.
+            // Update flattened dispute flag
+            deal.is_resolved.set(false);
+            deal.ruling.set(U8::from(0));
+
+            (bond, deal.freelancer.get(), streamed)
+        };
+
+        self.collect(token_addr, caller, bond)?;
+
+        for (milestone_index, vested) in streamed {
+            let fee_amount =
+                safe_mul(vested, U256::from(RELEASE_FEE_BPS))? / U256::from(BPS_DENOMINATOR);
+            let amount = safe_sub(vested, fee_amount)?;
+            self.accrue_fee(token_addr, fee_amount)?;
+            self.transfer_out(token_addr, freelancer, amount)?;
+
+            log(
+                self.vm(),
+                StreamClaimed {
+                    deal_id,
+                    milestone_index,
+                    freelancer,
+                    amount,
+                },
+            );
+        }
 
         // Emit event with details
         log(
@@ -383,36 +2885,111 @@ impl ArbiSecure {
                 initiator: caller,
             },
         );
+        if bond > U256::ZERO {
+            log(
+                self.vm(),
+                DisputeBondPosted {
+                    deal_id,
+                    party: caller,
+                    amount: bond,
+                },
+            );
+        }
+        log(
+            self.vm(),
+            MilestonesDisputed {
+                deal_id,
+                milestone_indices,
+            },
+        );
+
+        Ok(())
     }
 
-    /// Resolves an active dispute by distributing the remaining funds between the client
-    /// and freelancer according to the arbiter's ruling, after deducting the arbiter's fee.
-    pub fn resolve_dispute(&mut self, deal_id: U256, client_share: U256, freelancer_share: U256) {
-        let caller = self.vm().msg_sender();
+    /// Records a ruling. Without an appeal window it settles immediately; otherwise the funds
+    /// stay locked until `execute_ruling` or an appeal.
+    fn issue_ruling(
+        &mut self,
+        deal_id: U256,
+        client_share: U256,
+        freelancer_share: U256,
+        arbiters: Vec<Address>,
+    ) -> Result<(), Vec<u8>> {
+        let window = self.appeal_window.get();
+        if window == U256::ZERO {
+            return self.settle_dispute(
+                deal_id,
+                client_share,
+                freelancer_share,
+                arbiters,
+                Address::ZERO,
+                true,
+            );
+        }
 
-        let (client, freelancer, arbiter_addr, net_client, net_freelancer, fee, token_addr) = {
+        let deadline = safe_add(U256::from(self.vm().block_timestamp()), window)?;
+        require(deadline <= U256::from(u64::MAX), "Time");
+        {
             let mut deal = self.deals.setter(deal_id);
 
-            let status_val = deal.status.get().to::<u8>();
-            let status = DealStatus::from_u8(status_val).expect("BadS");
+            deal.is_resolved.set(true);
+            deal.pending_client_share.set(client_share);
+            deal.pending_freelancer_share.set(freelancer_share);
+            deal.appeal_deadline.set(U64::from(deadline));
+            deal.ruling_arbiters.erase();
+            for arbiter in arbiters.iter() {
+                deal.ruling_arbiters.push(*arbiter);
+            }
+        }
 
-            require(status == DealStatus::Disputed, "NotDisp");
-            require(!deal.is_resolved.get(), "Res");
+        log(
+            self.vm(),
+            RulingIssued {
+                deal_id,
+                client_share,
+                freelancer_share,
+                appeal_deadline: deadline,
+            },
+        );
 
-            let arbiter_addr = deal.arbiter.get();
-            require(caller == arbiter_addr, "NotArb");
+        Ok(())
+    }
+
+    /// Pays out a decided dispute: deducts the arbiter fee, pays the appeal `court` its share
+    /// (if any) and splits the rest evenly between the arbiters who ruled, closes the deal and,
+    /// if their ruling was `upheld`, credits their track records.
+    fn settle_dispute(
+        &mut self,
+        deal_id: U256,
+        client_share: U256,
+        freelancer_share: U256,
+        arbiters: Vec<Address>,
+        court: Address,
+        upheld: bool,
+    ) -> Result<(), Vec<u8>> {
+        let role = self.role_of(
+            deal_id,
+            self.vm().msg_sender(),
+            Action::Settle { completes: false },
+        );
 
-            // Use remaining_amount instead of looping milestones
-            let remaining_amount = deal.remaining_amount.get();
+        let (client, freelancer, winner, total_payout, payout, fee_mode, token_addr) = {
+            let mut deal = self.deals.setter(deal_id);
 
-            let total_payout = client_share + freelancer_share;
-            require(total_payout <= remaining_amount, "Over");
+            let status_val = deal.status.get().to::<u8>();
+            let status = DealStatus::from_u8(status_val).expect("BadS");
 
-            // Arbiter Fee (5%)
-            let fee = total_payout * U256::from(500) / U256::from(10000);
-            let net_client = client_share - (client_share * U256::from(500) / U256::from(10000));
-            let net_freelancer =
-                freelancer_share - (freelancer_share * U256::from(500) / U256::from(10000));
+            // The whole disputed amount is paid out; anything the ruling left unallocated
+            // is refunded to the client
+            let total_payout = deal.disputed_amount.get();
+            let fee_mode = FeeMode::from_u8(deal.fee_mode.get().to::<u8>()).expect("Fee");
+            let payout = split_dispute_payout(
+                total_payout,
+                client_share,
+                freelancer_share,
+                U256::from(ARBITER_FEE_BPS),
+                fee_mode,
+            )?;
 
             let token_addr = deal.token.get();
             let client = deal.client.get();
@@ -421,50 +2998,99 @@ impl ArbiSecure {
             // Update Dispute
             deal.is_resolved.set(true);
 
+            // The client's gross share includes the refund of anything left unallocated,
+            // as in `split_dispute_payout`; whoever receives more wins the ruling
+            let client_gross = safe_sub(total_payout, freelancer_share)?;
             // 1=Client, 2=Freelancer, 3=Split
-            if client_share > freelancer_share {
-                deal.ruling.set(U8::from(1));
-            } else if freelancer_share > client_share {
-                deal.ruling.set(U8::from(2));
+            let (ruling, winner) = if client_gross > freelancer_share {
+                (1, client)
+            } else if freelancer_share > client_gross {
+                (2, freelancer)
             } else {
-                deal.ruling.set(U8::from(3));
+                (3, Address::ZERO)
+            };
+            deal.ruling.set(U8::from(ruling));
+
+            // Settle the disputed milestones
+            for i in 0..deal.milestones.len() {
+                let mut milestone = deal.milestones.setter(i).expect("NoMs");
+                if milestone.is_disputed.get() {
+                    milestone.is_disputed.set(false);
+                    milestone.is_released.set(true);
+                }
             }
+            let remaining = safe_sub(deal.remaining_amount.get(), deal.disputed_amount.get())?;
+            deal.remaining_amount.set(remaining);
+            deal.disputed_amount.set(U256::ZERO);
+            deal.votes.erase();
 
-            // Close Deal
-            deal.status.set(U8::from(DealStatus::Completed.as_u8()));
+            // Resume the deal, or close it once nothing is left
+            let completes = remaining == U256::ZERO && !is_running_retainer(&deal);
+            let next = advance(status, Action::Settle { completes }, role);
+            deal.status.set(U8::from(next.as_u8()));
 
             (
                 client,
                 freelancer,
-                arbiter_addr,
-                net_client,
-                net_freelancer,
-                fee,
+                winner,
+                total_payout,
+                payout,
+                fee_mode,
                 token_addr,
             )
         };
+        let DisputePayout {
+            net_client,
+            net_freelancer,
+            fee,
+        } = payout;
+
+        // The appeal court takes its cut; the ruling arbiters split the rest and the first one
+        // absorbs the rounding dust
+        let court_fee = if court == Address::ZERO {
+            U256::ZERO
+        } else {
+            safe_mul(fee, U256::from(APPEAL_COURT_FEE_BPS))? / U256::from(BPS_DENOMINATOR)
+        };
+        let arbiter_fee = safe_sub(fee, court_fee)?;
+        let arbiter_count = U256::from(arbiters.len());
+        let fee_share = arbiter_fee / arbiter_count;
+        let fee_dust = safe_sub(arbiter_fee, safe_mul(fee_share, arbiter_count)?)?;
 
         // Transfers
-        if token_addr == Address::ZERO {
-            if net_client > U256::ZERO {
-                let _ = self.vm().transfer_eth(client, net_client);
-            }
-            if net_freelancer > U256::ZERO {
-                let _ = self.vm().transfer_eth(freelancer, net_freelancer);
-            }
-            if fee > U256::ZERO {
-                let _ = self.vm().transfer_eth(arbiter_addr, fee);
-            }
-        } else {
-            let token = IERC20::new(token_addr);
-            if net_client > U256::ZERO {
-                let _ = token.transfer(&mut *self, client, net_client);
-            }
-            if net_freelancer > U256::ZERO {
-                let _ = token.transfer(&mut *self, freelancer, net_freelancer);
-            }
-            if fee > U256::ZERO {
-                let _ = token.transfer(&mut *self, arbiter_addr, fee);
+        self.transfer_out(token_addr, client, net_client)?;
+        self.transfer_out(token_addr, freelancer, net_freelancer)?;
+        self.transfer_out(token_addr, court, court_fee)?;
+        for (i, arbiter) in arbiters.iter().enumerate() {
+            let amount = if i == 0 {
+                safe_add(fee_share, fee_dust)?
+            } else {
+                fee_share
+            };
+            self.transfer_out(token_addr, *arbiter, amount)?;
+        }
+
+        self.release_dispute_bonds(deal_id, winner, &arbiters)?;
+
+        // Update arbiter track records
+        let registry_addr = self.arbiter_registry.get();
+        if registry_addr != Address::ZERO && upheld {
+            for arbiter in arbiters.iter() {
+                let call = IArbiterRegistry::recordResolutionCall {
+                    arbiter: *arbiter,
+                    deal_id,
+                    amount: total_payout,
+                };
+                // A failing registry must not hold the payout hostage
+                if self.call_contract(registry_addr, call).is_err() {
+                    log(
+                        self.vm(),
+                        ResolutionReportFailed {
+                            deal_id,
+                            arbiter: *arbiter,
+                        },
+                    );
+                }
             }
         }
 
@@ -475,63 +3101,363 @@ impl ArbiSecure {
                 client_amount: net_client,
                 freelancer_amount: net_freelancer,
                 arbiter_fee: fee,
+                fee_mode: fee_mode.as_u8(),
             },
         );
+
+        Ok(())
     }
-    /// Retrieves detailed status information regarding a specific milestone within a deal.
-    pub fn get_milestone(
+
+    /// Pays out the current dispute's bonds. The winner recovers their own bond and takes the
+    /// loser's; a bond the winner never matched goes to `arbiters` instead. Without a winner
+    /// (`Address::ZERO`) every bond is refunded.
+    fn release_dispute_bonds(
+        &mut self,
+        deal_id: U256,
+        winner: Address,
+        arbiters: &[Address],
+    ) -> Result<(), Vec<u8>> {
+        let (token_addr, initiator, respondent, bond, matched) = {
+            let mut deal = self.deals.setter(deal_id);
+
+            let bond = deal.dispute_bond.get();
+            let matched = deal.bond_matched.get();
+            deal.dispute_bond.set(U256::ZERO);
+            deal.bond_matched.set(false);
+
+            let initiator = deal.dispute_initiator.get();
+            let respondent = if initiator == deal.client.get() {
+                deal.freelancer.get()
+            } else {
+                deal.client.get()
+            };
+            (deal.token.get(), initiator, respondent, bond, matched)
+        };
+        if bond == U256::ZERO {
+            return Ok(());
+        }
+
+        let respondent_bond = if matched { bond } else { U256::ZERO };
+        let posted = safe_add(bond, respondent_bond)?;
+        let (initiator_amount, respondent_amount, arbiter_amount) = if winner == initiator {
+            (posted, U256::ZERO, U256::ZERO)
+        } else if winner == respondent && matched {
+            (U256::ZERO, posted, U256::ZERO)
+        } else if winner == respondent {
+            (U256::ZERO, U256::ZERO, bond)
+        } else {
+            (bond, respondent_bond, U256::ZERO)
+        };
+
+        self.transfer_out(token_addr, initiator, initiator_amount)?;
+        self.transfer_out(token_addr, respondent, respondent_amount)?;
+        if arbiter_amount > U256::ZERO {
+            // Split evenly; the first arbiter absorbs the rounding dust
+            let arbiter_count = U256::from(arbiters.len());
+            let share = arbiter_amount / arbiter_count;
+            let dust = safe_sub(arbiter_amount, safe_mul(share, arbiter_count)?)?;
+            for (i, arbiter) in arbiters.iter().enumerate() {
+                let amount = if i == 0 { share + dust } else { share };
+                self.transfer_out(token_addr, *arbiter, amount)?;
+            }
+        }
+
+        log(
+            self.vm(),
+            DisputeBondsReleased {
+                deal_id,
+                initiator_amount,
+                respondent_amount,
+                arbiter_amount,
+            },
+        );
+        Ok(())
+    }
+
+    /// Pays escrowed ETH (token == Address::ZERO) or ERC20 tokens out to `to`.
+    fn transfer_out(
+        &mut self,
+        token_addr: Address,
+        to: Address,
+        amount: U256,
+    ) -> Result<(), Vec<u8>> {
+        let escrowed = self.total_escrowed.get(token_addr);
+        self.total_escrowed
+            .setter(token_addr)
+            .set(safe_sub(escrowed, amount)?);
+
+        self.send(token_addr, to, amount)
+    }
+
+    /// Moves a protocol fee out of the escrowed balance into the withdrawable fee pool.
+    fn accrue_fee(&mut self, token_addr: Address, fee: U256) -> Result<(), Vec<u8>> {
+        let escrowed = self.total_escrowed.get(token_addr);
+        self.total_escrowed
+            .setter(token_addr)
+            .set(safe_sub(escrowed, fee)?);
+
+        let fees = self.accrued_fees.get(token_addr);
+        self.accrued_fees
+            .setter(token_addr)
+            .set(safe_add(fees, fee)?);
+        Ok(())
+    }
+
+    /// Calls `to` through the host VM and decodes the return data of `call`.
+    fn call_contract<C: SolCall>(&mut self, to: Address, call: C) -> Result<C::Return, Vec<u8>> {
+        let data = self.vm().call(&self, to, &call.abi_encode())?;
+        C::abi_decode_returns(&data, true).map_err(|_| b"Decode".to_vec())
+    }
+
+    /// Static-calls `to` through the host VM and decodes the return data of `call`.
+    fn static_call_contract<C: SolCall>(&self, to: Address, call: C) -> Result<C::Return, Vec<u8>> {
+        let data = self.vm().static_call(&self, to, &call.abi_encode())?;
+        C::abi_decode_returns(&data, true).map_err(|_| b"Decode".to_vec())
+    }
+
+    /// Sends ETH or ERC20 tokens, reverting with `TransferFailed` if the transfer fails.
+    fn send(&mut self, token_addr: Address, to: Address, amount: U256) -> Result<(), Vec<u8>> {
+        if amount == U256::ZERO {
+            return Ok(());
+        }
+
+        let success = if token_addr == Address::ZERO {
+            self.vm().transfer_eth(to, amount).is_ok()
+        } else {
+            let call = IERC20::transferCall {
+                recipient: to,
+                amount,
+            };
+            matches!(self.call_contract(token_addr, call), Ok(result) if result._0)
+        };
+
+        if !success {
+            return Err(TransferFailed {
+                token: token_addr,
+                recipient: to,
+                amount,
+            }
+            .abi_encode());
+        }
+        Ok(())
+    }
+
+    /// Pulls ETH (via msg.value) or approved ERC20 tokens from `from` into the escrow.
+    fn collect(&mut self, token_addr: Address, from: Address, amount: U256) -> Result<(), Vec<u8>> {
+        if token_addr == Address::ZERO {
+            require(self.vm().msg_value() == amount, "BadETH");
+        } else if amount > U256::ZERO {
+            let call = IERC20::transferFromCall {
+                sender: from,
+                recipient: self.vm().contract_address(),
+                amount,
+            };
+            match self.call_contract(token_addr, call) {
+                Ok(result) => require(result._0, "TknF"),
+                Err(_) => panic!("TknF"),
+            }
+        }
+
+        let escrowed = self.total_escrowed.get(token_addr);
+        self.total_escrowed
+            .setter(token_addr)
+            .set(safe_add(escrowed, amount)?);
+        Ok(())
+    }
+
+    /// Checks that the caller may perform `action`, that `old_arbiter` holds a seat on the deal
+    /// that can still be vacated and that `new_arbiter` is eligible to take it.
+    fn require_reassignable(
         &self,
         deal_id: U256,
-        index: U256,
-    ) -> Result<(U256, bool, U256, bool), Vec<u8>> {
+        action: Action,
+        old_arbiter: Address,
+        new_arbiter: Address,
+    ) {
+        let role = self.role_of(deal_id, self.vm().msg_sender(), action);
         let deal = self.deals.get(deal_id);
-        if index >= U256::from(deal.milestones.len()) {
-            return Err(Vec::new()); // Index out of bounds
+
+        let status_val = deal.status.get().to::<u8>();
+        let status = DealStatus::from_u8(status_val).expect("BadS");
+        advance(status, action, role);
+        require(
+            status != DealStatus::Disputed || !deal.is_resolved.get(),
+            "Res",
+        );
+
+        let mut seats = Vec::new();
+        if deal.panel.is_empty() {
+            seats.push(deal.arbiter.get());
+        } else {
+            for i in 0..deal.panel.len() {
+                seats.push(deal.panel.get(i).unwrap_or(Address::ZERO));
+            }
         }
+        require(seats.contains(&old_arbiter), "Seat");
 
-        let milestone_accessor = deal.milestones.get(index.to::<usize>());
-        if milestone_accessor.is_none() {
-            return Err(Vec::new());
+        if new_arbiter != Address::ZERO {
+            require(!seats.contains(&new_arbiter), "Dup");
+            require(
+                new_arbiter != deal.client.get() && new_arbiter != deal.freelancer.get(),
+                "Party",
+            );
         }
-        let milestone = milestone_accessor.unwrap();
 
-        Ok((
-            milestone.amount.get(),
-            milestone.is_released.get(),
-            milestone.end_timestamp.get(),
-            milestone.requires_approval.get(),
-        ))
+        // Panelists who already ruled keep their seat
+        for i in 0..deal.votes.len() {
+            let vote = deal.votes.get(i).expect("Vote");
+            require(vote.arbiter.get() != old_arbiter, "Voted");
+        }
     }
 
-    /// Retrieves the client (buyer) address associated with the specified deal.
-    pub fn get_deal_client(&self, deal_id: U256) -> Address {
-        self.deals.get(deal_id).client.get()
+    /// Draws an arbiter for the deal's vacant seat from the registry using the parties' combined
+    /// secrets and resets the commit-reveal round. The parties and sitting panelists are excluded.
+    fn select_random_arbiter(&mut self, deal_id: U256) {
+        let (exclude, seed) = {
+            let mut deal = self.deals.setter(deal_id);
+
+            let mut data = deal.arbiter_seed.get().to_be_bytes::<32>().to_vec();
+            data.extend_from_slice(&deal_id.to_be_bytes::<32>());
+            let seed = U256::from_be_bytes(keccak(&data).0);
+
+            deal.client_seed_commit.set(B256::ZERO);
+            deal.freelancer_seed_commit.set(B256::ZERO);
+            deal.client_seed_revealed.set(false);
+            deal.freelancer_seed_revealed.set(false);
+            deal.arbiter_seed.set(U256::ZERO);
+            deal.seed_deadline.set(U64::ZERO);
+
+            let mut exclude = vec![deal.client.get(), deal.freelancer.get()];
+            for i in 0..deal.panel.len() {
+                let seat = deal.panel.get(i).unwrap_or(Address::ZERO);
+                if seat != Address::ZERO {
+                    exclude.push(seat);
+                }
+            }
+            (exclude, seed)
+        };
+
+        let call = IArbiterRegistry::selectArbiterCall { seed, exclude };
+        let arbiter = match self.static_call_contract(self.arbiter_registry.get(), call) {
+            Ok(selected) => selected._0,
+            Err(_) => panic!("Reg"),
+        };
+
+        {
+            let mut deal = self.deals.setter(deal_id);
+            if deal.panel.is_empty() {
+                deal.arbiter.set(arbiter);
+            }
+            for i in 0..deal.panel.len() {
+                let mut seat = deal.panel.setter(i).expect("Seat");
+                if seat.get() == Address::ZERO {
+                    seat.set(arbiter);
+                    break;
+                }
+            }
+        }
+
+        log(
+            self.vm(),
+            ArbiterSelected {
+                deal_id,
+                arbiter,
+                seed,
+            },
+        );
     }
 
-    /// Retrieves the freelancer (seller) address associated with the specified deal.
-    pub fn get_deal_freelancer(&self, deal_id: U256) -> Address {
-        self.deals.get(deal_id).freelancer.get()
+    /// Swaps an arbiter seat on the deal and clears any pending reassignment proposal.
+    fn reassign_arbiter(&mut self, deal_id: U256, old_arbiter: Address, new_arbiter: Address) {
+        {
+            let mut deal = self.deals.setter(deal_id);
+
+            if deal.arbiter.get() == old_arbiter {
+                deal.arbiter.set(new_arbiter);
+            }
+            for i in 0..deal.panel.len() {
+                let mut seat = deal.panel.setter(i).expect("Seat");
+                if seat.get() == old_arbiter {
+                    seat.set(new_arbiter);
+                    break;
+                }
+            }
+
+            deal.replaced_arbiter.set(Address::ZERO);
+            deal.proposed_arbiter.set(Address::ZERO);
+            deal.arbiter_proposer.set(Address::ZERO);
+        }
+
+        log(
+            self.vm(),
+            ArbiterReassigned {
+                deal_id,
+                old_arbiter,
+                new_arbiter,
+            },
+        );
     }
+}
 
-    /// Retrieves the current execution status of the specified deal.
-    pub fn get_deal_status(&self, deal_id: U256) -> U256 {
-        U256::from(self.deals.get(deal_id).status.get().to::<u8>())
+/// Picks the median panel ruling, ordered by client share. The chosen ruling is one that a
+/// panelist actually submitted, so its shares never exceed the disputed amount.
+fn median_ruling(rulings: &mut [(U256, U256)]) -> (U256, U256) {
+    rulings.sort();
+    rulings[rulings.len() / 2]
+}
+
+/// Commitment a party submits to `commit_arbiter_seed`: keccak256(secret ++ party).
+/// Binding the party's address prevents the counterparty from replaying the commitment.
+pub fn arbiter_seed_commitment(secret: U256, party: Address) -> B256 {
+    let mut data = secret.to_be_bytes::<32>().to_vec();
+    data.extend_from_slice(party.as_slice());
+    keccak(&data)
+}
+
+/// Consults the deal state machine, reverting if the transition is not allowed.
+fn advance(status: DealStatus, action: Action, role: Role) -> DealStatus {
+    match state_machine::transition(status, action, role) {
+        Ok(next) => next,
+        Err(_) => panic!("BadSt"),
     }
+}
 
-    /// Retrieves the remaining unreleased funds allocated to the specified deal.
-    pub fn get_deal_amount(&self, deal_id: U256) -> U256 {
-        self.deals.get(deal_id).remaining_amount.get()
+/// Whether the deal is a retainer that has not been ended, and so stays open between
+/// funded periods.
+fn is_running_retainer(deal: &Deal) -> bool {
+    deal.period_length.get() != U64::ZERO && deal.retainer_end.get() == U64::ZERO
+}
+
+/// Whether the deal has an arbiter seat waiting to be filled by the commit-reveal draw.
+fn has_vacant_seat(deal: &Deal) -> bool {
+    if deal.panel.is_empty() {
+        return deal.arbiter.get() == Address::ZERO;
     }
+    (0..deal.panel.len()).any(|i| deal.panel.get(i) == Some(Address::ZERO))
+}
 
-    /// Retrieves the designated arbiter address assigned to oversee the specified deal.
-    pub fn get_deal_arbiter(&self, deal_id: U256) -> Address {
-        self.deals.get(deal_id).arbiter.get()
+/// End timestamps of `periods` consecutive periods of `period_length` starting at `from`.
+fn period_ends(from: u64, period_length: u64, periods: u64) -> Vec<u64> {
+    let mut ends = Vec::new();
+    let mut end = from;
+    for _ in 0..periods {
+        end = end.checked_add(period_length).expect("Time");
+        ends.push(end);
     }
+    ends
 }
 
-// ============================================================================
-// Helper Functions (Internal)
-// ============================================================================
+/// Portion of a stream's `outstanding` amount vested at `now`, vesting linearly from `start`
+/// to `end`.
+fn vested_amount(outstanding: U256, start: u64, end: u64, now: u64) -> Result<U256, Vec<u8>> {
+    if now <= start {
+        return Ok(U256::ZERO);
+    }
+    if now >= end {
+        return Ok(outstanding);
+    }
+    Ok(safe_mul(outstanding, U256::from(now - start))? / U256::from(end - start))
+}
 
 fn require(condition: bool, _message: &str) {
     if !condition {
@@ -546,6 +3472,38 @@ fn require(condition: bool, _message: &str) {
 sol! {
     event MilestoneReleased(uint256 indexed deal_id, uint256 milestone_index, address freelancer, uint256 amount);
     event DisputeRaised(uint256 indexed deal_id, address initiator);
-    event DisputeResolved(uint256 indexed deal_id, uint256 client_amount, uint256 freelancer_amount, uint256 arbiter_fee);
+    event DisputeWithdrawn(uint256 indexed deal_id, address initiator);
+    event RetainerFunded(uint256 indexed deal_id, uint64 periods, uint64 funded_until);
+    event RetainerEnded(uint256 indexed deal_id, address ended_by, uint64 ends_at, uint256 refund);
+    event StreamClaimed(uint256 indexed deal_id, uint256 milestone_index, address freelancer, uint256 amount);
+    event StreamCancelled(uint256 indexed deal_id, uint256 milestone_index, address cancelled_by, uint256 freelancer_amount, uint256 refund);
+    event DisputeBondPosted(uint256 indexed deal_id, address party, uint256 amount);
+    event DisputeBondsReleased(uint256 indexed deal_id, uint256 initiator_amount, uint256 respondent_amount, uint256 arbiter_amount);
+    event MilestonesDisputed(uint256 indexed deal_id, uint256[] milestone_indices);
+    event MilestoneRuled(uint256 indexed deal_id, uint256 milestone_index, uint8 outcome, uint256 client_amount);
+    event DisputeResolved(uint256 indexed deal_id, uint256 client_amount, uint256 freelancer_amount, uint256 arbiter_fee, uint8 fee_mode);
+    event ResolutionReportFailed(uint256 indexed deal_id, address arbiter);
     event DealCreated(uint256 indexed deal_id, address client, address freelancer, uint256 amount, address token);
+    event FeesWithdrawn(address indexed token, address to, uint256 amount);
+    event ArbiterReassigned(uint256 indexed deal_id, address old_arbiter, address new_arbiter);
+    event ArbiterSelected(uint256 indexed deal_id, address arbiter, uint256 seed);
+    event RulingIssued(uint256 indexed deal_id, uint256 client_share, uint256 freelancer_share, uint256 appeal_deadline);
+    event RulingAppealed(uint256 indexed deal_id, address appellant, uint256 bond);
+    event RulingSubmitted(uint256 indexed deal_id, address arbiter, uint256 client_share, uint256 freelancer_share);
+    event Initialized(address indexed admin, uint64 storage_version);
+    event Upgraded(address indexed implementation);
+    event Migrated(uint64 from_version, uint64 to_version);
+    event RoleGranted(bytes32 indexed role, address indexed account, address indexed sender);
+    event RoleRevoked(bytes32 indexed role, address indexed account, address indexed sender);
+    event Paused(address account);
+    event Unpaused(address account);
+    event TokenListed(address indexed token, bool allowed);
+    event SettlementProposed(uint256 indexed deal_id, address proposer, uint256 client_share, uint256 freelancer_share);
+    event SettlementAccepted(uint256 indexed deal_id, uint256 client_amount, uint256 freelancer_amount, uint256 fee);
+    event OperationScheduled(uint256 indexed operation_id, uint8 kind, bytes params, uint64 eta);
+    event OperationCancelled(uint256 indexed operation_id);
+    event OperationExecuted(uint256 indexed operation_id);
+    event AdminProposed(address indexed admin, address indexed pending_admin);
+    event AdminTransferred(address indexed previous_admin, address indexed new_admin);
+    event AdminProposalCancelled(address indexed pending_admin);
 }
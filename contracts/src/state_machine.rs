@@ -0,0 +1,141 @@
+//! Deal lifecycle state machine.
+//!
+//! Every entrypoint that depends on a deal's status consults [`transition`] instead of
+//! checking the status inline, so the full set of legal moves lives in one table.
+
+use crate::DealStatus;
+
+/// Operation performed on a deal
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+    /// Create and fund the deal
+    Create,
+    /// Release a milestone; `completes` is set when it is the last unreleased milestone
+    Release { completes: bool },
+    /// Freeze some or all unreleased milestones
+    RaiseDispute,
+    /// Submit a ruling (or a panel vote) on the open dispute
+    Rule,
+    /// Appeal a pending ruling
+    Appeal,
+    /// Rule on an appealed dispute
+    RuleAppeal,
+    /// Pay out a decided dispute; `completes` is set when nothing is left in escrow afterwards
+    Settle { completes: bool },
+    /// Propose or fill a replacement for an arbiter seat
+    ReassignArbiter,
+    /// Step down from an arbiter seat
+    Recuse,
+    /// Commit or reveal a seed for drawing the arbiter from the registry
+    DrawArbiter,
+    /// Offer the counterparty a split of everything left in escrow
+    ProposeSettlement,
+    /// Accept the counterparty's offer, paying out and closing the deal
+    AcceptSettlement,
+    /// Withdraw the open dispute, returning the deal to `resumes`
+    WithdrawDispute { resumes: DealStatus },
+    /// Match the dispute bond posted by the initiator
+    MatchBond,
+    /// Fund further periods of a retainer
+    Fund,
+    /// End a retainer, closing the deal if no notice periods remain
+    EndRetainer { completes: bool },
+    /// Cancel a stream, paying out what has vested and refunding the rest; `completes` as for
+    /// `Release`
+    CancelStream { completes: bool },
+}
+
+/// Relationship of the caller to the deal
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Role {
+    Client,
+    Freelancer,
+    /// The deal's arbiter or one of its panelists
+    Arbiter,
+    /// The configured appeal court
+    Court,
+    /// Anyone else
+    Other,
+}
+
+/// Reason a transition is rejected
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TransitionError {
+    /// The action is not possible in the deal's current status
+    InvalidStatus,
+    /// The caller's role may not perform the action
+    Unauthorized,
+}
+
+impl Action {
+    /// Whether `role` may perform this action
+    pub fn permits(self, role: Role) -> bool {
+        match self {
+            Action::Create | Action::Fund => role == Role::Client,
+            // Releases go to the freelancer and settlements follow a ruling; anyone can trigger them
+            Action::Release { .. } | Action::Settle { .. } => true,
+            Action::RaiseDispute
+            | Action::Appeal
+            | Action::ReassignArbiter
+            | Action::DrawArbiter
+            | Action::ProposeSettlement
+            | Action::AcceptSettlement
+            | Action::WithdrawDispute { .. }
+            | Action::MatchBond
+            | Action::EndRetainer { .. }
+            | Action::CancelStream { .. } => matches!(role, Role::Client | Role::Freelancer),
+            Action::Rule | Action::Recuse => role == Role::Arbiter,
+            Action::RuleAppeal => role == Role::Court,
+        }
+    }
+}
+
+/// Returns the status a deal in `status` moves to when `role` performs `action`.
+pub fn transition(
+    status: DealStatus,
+    action: Action,
+    role: Role,
+) -> Result<DealStatus, TransitionError> {
+    use DealStatus::*;
+
+    let next = match (status, action) {
+        (Created, Action::Create) => Funded,
+        (Funded | Active, Action::Release { completes: true }) => Completed,
+        (Funded | Active, Action::Release { completes: false }) => Active,
+        (Funded | Active, Action::CancelStream { completes: true }) => Completed,
+        (Funded | Active, Action::CancelStream { completes: false }) => Active,
+        // Milestones outside the dispute's scope keep flowing; disputed funds are always left
+        (Disputed, Action::Release { completes: false }) => Disputed,
+        (Disputed, Action::CancelStream { completes: false }) => Disputed,
+        (Funded | Active, Action::RaiseDispute) => Disputed,
+        (Disputed, Action::Rule | Action::Appeal | Action::RuleAppeal) => Disputed,
+        (Disputed, Action::MatchBond) => Disputed,
+        (Disputed, Action::Settle { completes: true }) => Completed,
+        (Disputed, Action::Settle { completes: false }) => Active,
+        (
+            Disputed,
+            Action::WithdrawDispute {
+                resumes: resumes @ (Funded | Active),
+            },
+        ) => resumes,
+        (
+            Funded | Active | Disputed,
+            Action::ReassignArbiter
+            | Action::Recuse
+            | Action::DrawArbiter
+            | Action::ProposeSettlement,
+        ) => status,
+        (Funded | Active | Disputed, Action::Fund | Action::EndRetainer { completes: false }) => {
+            status
+        }
+        (Funded | Active, Action::EndRetainer { completes: true }) => Completed,
+        // Parties can settle between themselves at any point, even mid-dispute
+        (Funded | Active | Disputed, Action::AcceptSettlement) => Completed,
+        _ => return Err(TransitionError::InvalidStatus),
+    };
+
+    if !action.permits(role) {
+        return Err(TransitionError::Unauthorized);
+    }
+    Ok(next)
+}
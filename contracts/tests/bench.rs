@@ -0,0 +1,245 @@
+//! Gas benchmarks for `ArbiSecure` and `ArbiterRegistry` on a local Nitro node.
+//!
+//! Each scenario is measured with `eth_estimateGas` and reported in gas and ink. The run fails
+//! if any scenario exceeds its budget in `bench/budgets.toml`. Usually invoked through
+//! `scripts/bench.sh`, which also checks the compressed WASM sizes:
+//!
+//! ```bash
+//! ARBISECURE_ADDRESS=0x... REGISTRY_ADDRESS=0x... cargo test --test bench -- --ignored --nocapture
+//! ```
+//!
+//! With `BASELINE_ADDRESS` set to a deployment of the unpacked baseline, `create_deal` is also
+//! measured there and the run fails if the current revision costs more.
+
+use ethers::{
+    prelude::*,
+    types::{Address, U256},
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const RPC_URL: &str = "http://127.0.0.1:8547";
+// Funded dev account from deploy_local.sh
+const PRIVATE_KEY: &str = "0xb6b15c8cb491557369f3c7d2c287b053eb229daa9c22138887752191c9520659";
+// ArbWasm precompile, which reports the current ink price
+const ARB_WASM: &str = "0x0000000000000000000000000000000000000071";
+
+abigen!(
+    ArbiSecure,
+    r#"[
+         function createDeal(uint256 _ref_id, address freelancer, address arbiter, address token, uint256 amount, uint256[] milestone_amounts, uint256[] milestone_end_times, uint256[] milestone_approvals) external payable returns (uint256)
+         function releaseMilestone(uint256 deal_id, uint256 milestone_index) external
+         function raiseDispute(uint256 deal_id) external
+         function resolveDispute(uint256 deal_id, uint256 client_share, uint256 freelancer_share) external
+     ]"#,
+);
+
+abigen!(
+    ArbiterRegistry,
+    r#"[
+         function setEscrow(address escrow) external
+         function getActiveArbiters() external view returns (address[])
+         function getArbiterStatus(address arbiter) external view returns (bool, uint256, uint256)
+     ]"#,
+);
+
+abigen!(
+    ArbWasm,
+    r#"[
+         function inkPrice() external view returns (uint32)
+     ]"#,
+);
+
+type Client = SignerMiddleware<Provider<Http>, LocalWallet>;
+
+/// Measured scenarios and their budgets
+struct Report {
+    budgets: HashMap<String, u64>,
+    ink_price: u64,
+    over_budget: Vec<String>,
+}
+
+impl Report {
+    fn record(&mut self, name: &str, gas: U256) {
+        let gas = gas.as_u64();
+        let budget = self.budgets.get(name).copied();
+        let status = match budget {
+            Some(budget) if gas > budget => {
+                self.over_budget.push(name.to_string());
+                "OVER"
+            }
+            Some(_) => "ok",
+            None => "no budget",
+        };
+        println!(
+            "{:<40} {:>10} {:>16} {:>10} {}",
+            name,
+            gas,
+            gas * self.ink_price,
+            budget.map(|b| b.to_string()).unwrap_or_default(),
+            status
+        );
+    }
+
+    /// Reports the saving of `gas` over the same scenario on the baseline deployment.
+    fn compare(&mut self, name: &str, gas: U256, baseline: U256) {
+        let (gas, baseline) = (gas.as_u64(), baseline.as_u64());
+        let saved = baseline as i64 - gas as i64;
+        let status = if saved < 0 {
+            self.over_budget.push(format!("{name} (above baseline)"));
+            "OVER"
+        } else {
+            "ok"
+        };
+        println!(
+            "{:<40} {:>10} {:>16} {:>10} {}",
+            format!("{name} (baseline)"),
+            baseline,
+            format!("saved {saved}"),
+            format!("{:.1}%", saved as f64 * 100.0 / baseline as f64),
+            status
+        );
+    }
+}
+
+/// Parses `key = value` lines, ignoring comments and digit separators.
+fn load_budgets() -> HashMap<String, u64> {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/bench/budgets.toml");
+    let contents = std::fs::read_to_string(path).expect("read budgets");
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            let value = value.trim().replace('_', "").parse().ok()?;
+            Some((key.trim().to_string(), value))
+        })
+        .collect()
+}
+
+#[tokio::test]
+#[ignore = "requires a local Nitro node"]
+async fn bench_gas() -> Result<(), Box<dyn std::error::Error>> {
+    let provider = Provider::<Http>::try_from(RPC_URL)?;
+    let chain_id = provider.get_chainid().await?;
+    let wallet: LocalWallet = PRIVATE_KEY.parse()?;
+    let wallet = wallet.with_chain_id(chain_id.as_u64());
+    let client = Arc::new(SignerMiddleware::new(provider, wallet));
+
+    let ink_price = ArbWasm::new(ARB_WASM.parse::<Address>()?, client.clone())
+        .ink_price()
+        .call()
+        .await?;
+
+    let mut report = Report {
+        budgets: load_budgets(),
+        ink_price: ink_price as u64,
+        over_budget: Vec::new(),
+    };
+
+    println!(
+        "{:<40} {:>10} {:>16} {:>10}",
+        "scenario", "gas", "ink", "budget"
+    );
+    bench_escrow(&client, &mut report).await?;
+    bench_registry(&client, &mut report).await?;
+
+    assert!(
+        report.over_budget.is_empty(),
+        "over budget: {:?}",
+        report.over_budget
+    );
+    Ok(())
+}
+
+async fn bench_escrow(
+    client: &Arc<Client>,
+    report: &mut Report,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let address: Address = std::env::var("ARBISECURE_ADDRESS")?.parse()?;
+    let contract = ArbiSecure::new(address, client.clone());
+
+    // The baseline keeps the original `createDeal` signature, so the same calls apply
+    let baseline = match std::env::var("BASELINE_ADDRESS") {
+        Ok(address) => Some(ArbiSecure::new(address.parse::<Address>()?, client.clone())),
+        Err(_) => None,
+    };
+
+    let freelancer = "0x3C44CdDdB6a900fa2b585dd299e03d12FA4293BC".parse::<Address>()?;
+    // The benchmark account arbitrates its own deals so it can resolve them
+    let arbiter = client.address();
+
+    // Time-locked, approval-gated milestones exercise every milestone field
+    let create = |contract: &ArbiSecure<Client>, count: usize| {
+        let amount = U256::from(count * 100);
+        contract
+            .create_deal(
+                U256::zero(),
+                freelancer,
+                arbiter,
+                Address::zero(),
+                amount,
+                vec![U256::from(100); count],
+                vec![U256::from(u32::MAX); count],
+                vec![U256::one(); count],
+            )
+            .value(amount)
+    };
+
+    for count in [1, 5, 20] {
+        let name = format!("arbisecure.create_deal_{count}");
+        let gas = create(&contract, count).estimate_gas().await?;
+        report.record(&name, gas);
+        if let Some(baseline) = &baseline {
+            let baseline_gas = create(baseline, count).estimate_gas().await?;
+            report.compare(&name, gas, baseline_gas);
+        }
+    }
+
+    // Lifecycle calls on a fresh two-milestone deal
+    let deal_id = create(&contract, 2).call().await?;
+    create(&contract, 2).send().await?.await?;
+
+    let gas = contract
+        .release_milestone(deal_id, U256::zero())
+        .estimate_gas()
+        .await?;
+    report.record("arbisecure.release_milestone", gas);
+
+    let gas = contract.raise_dispute(deal_id).estimate_gas().await?;
+    report.record("arbisecure.raise_dispute", gas);
+
+    contract.raise_dispute(deal_id).send().await?.await?;
+    let gas = contract
+        .resolve_dispute(deal_id, U256::from(100), U256::from(100))
+        .estimate_gas()
+        .await?;
+    report.record("arbisecure.resolve_dispute", gas);
+
+    Ok(())
+}
+
+async fn bench_registry(
+    client: &Arc<Client>,
+    report: &mut Report,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let address: Address = std::env::var("REGISTRY_ADDRESS")?.parse()?;
+    let registry = ArbiterRegistry::new(address, client.clone());
+
+    // The registry must be deployed with the benchmark account as admin
+    let escrow: Address = std::env::var("ARBISECURE_ADDRESS")?.parse()?;
+    let gas = registry.set_escrow(escrow).estimate_gas().await?;
+    report.record("arbiter_registry.set_escrow", gas);
+
+    let gas = registry.get_active_arbiters().estimate_gas().await?;
+    report.record("arbiter_registry.get_active_arbiters", gas);
+
+    let gas = registry
+        .get_arbiter_status(client.address())
+        .estimate_gas()
+        .await?;
+    report.record("arbiter_registry.get_arbiter_status", gas);
+
+    Ok(())
+}
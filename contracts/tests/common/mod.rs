@@ -0,0 +1,368 @@
+//! In-process test harness for `ArbiSecure` on the stylus-test `TestVM`.
+//!
+//! External contracts are mocked at the call level: `MockErc20` answers the exact
+//! `transfer`/`transferFrom`/`balanceOf` calls a test expects and `MockRegistry` does the same
+//! for `ArbiterRegistry`. ETH is tracked with the VM's balances: value attached to a call is
+//! credited to `ESCROW`, and payouts are checked through the recipients' balances.
+
+#![allow(dead_code)]
+
+use alloy_primitives::{Address, B256, U256};
+use alloy_sol_types::{sol, SolCall, SolEvent, SolValue};
+use arbisecure_contracts::ArbiSecure;
+use stylus_sdk::{prelude::*, testing::*};
+
+pub const ESCROW: Address = Address::repeat_byte(0xE5);
+pub const ADMIN: Address = Address::repeat_byte(0xAD);
+pub const CLIENT: Address = Address::repeat_byte(0xC1);
+pub const FREELANCER: Address = Address::repeat_byte(0xF1);
+pub const ARBITER: Address = Address::repeat_byte(0xA1);
+pub const ARBITER_2: Address = Address::repeat_byte(0xA2);
+pub const ARBITER_3: Address = Address::repeat_byte(0xA3);
+pub const ARBITER_4: Address = Address::repeat_byte(0xA4);
+pub const ARBITER_5: Address = Address::repeat_byte(0xA5);
+pub const COURT: Address = Address::repeat_byte(0xCC);
+pub const STRANGER: Address = Address::repeat_byte(0x57);
+pub const TOKEN: Address = Address::repeat_byte(0x70);
+pub const REGISTRY: Address = Address::repeat_byte(0x4E);
+
+/// Deal status values as returned by `get_deal_status`
+pub const FUNDED: u64 = 1;
+pub const ACTIVE: u64 = 2;
+pub const DISPUTED: u64 = 3;
+pub const COMPLETED: u64 = 4;
+
+/// Retainer period length used by `Escrow::retainer`
+pub const PERIOD: u64 = 1_000;
+
+/// Fee modes accepted by `create_deal`
+pub const PROPORTIONAL: u8 = 0;
+pub const LOSER_PAYS: u8 = 1;
+pub const CLIENT_PAYS: u8 = 2;
+
+sol! {
+    interface IERC20 {
+        function transfer(address recipient, uint256 amount) external returns (bool);
+        function transferFrom(address sender, address recipient, uint256 amount) external returns (bool);
+        function balanceOf(address account) external view returns (uint256);
+    }
+
+    interface IArbiterRegistry {
+        function recordResolution(address arbiter, uint256 deal_id, uint256 amount) external;
+        function getArbiterStatus(address arbiter) external view returns (bool, uint256, uint256);
+        function selectArbiter(uint256 seed, address[] exclude) external view returns (address);
+    }
+}
+
+/// Shorthand for `U256::from`
+pub fn u(value: u64) -> U256 {
+    U256::from(value)
+}
+
+/// `ArbiSecure` deployed at `ESCROW`, initialized by `ADMIN` and with `TOKEN` listed
+pub struct Escrow {
+    pub vm: TestVM,
+    pub contract: ArbiSecure,
+}
+
+impl Escrow {
+    pub fn new() -> Self {
+        let vm = TestVM::default();
+        vm.set_contract_address(ESCROW);
+        vm.set_block_timestamp(1_000);
+
+        let mut contract = ArbiSecure::from(&vm);
+        vm.set_sender(ADMIN);
+        contract.constructor(ADMIN);
+
+        let mut escrow = Self { vm, contract };
+        let listing = escrow.contract.schedule_token_allowed(TOKEN, true).unwrap();
+        escrow.execute_after_delay(listing);
+        escrow
+    }
+
+    /// Makes `who` the sender of the next calls, with no value attached.
+    pub fn sender(&self, who: Address) -> &Self {
+        self.vm.set_sender(who);
+        self.vm.set_value(U256::ZERO);
+        self
+    }
+
+    /// Attaches `value` wei to the next call and credits it to `ESCROW`, as the EVM would.
+    pub fn value(&self, value: U256) -> &Self {
+        self.vm.set_value(value);
+        self.vm.set_balance(ESCROW, self.vm.balance(ESCROW) + value);
+        self
+    }
+
+    pub fn at(&self, timestamp: u64) -> &Self {
+        self.vm.set_block_timestamp(timestamp);
+        self
+    }
+
+    /// ETH balance of `who`, i.e. everything the contract has paid it.
+    pub fn eth_balance(&self, who: Address) -> U256 {
+        self.vm.balance(who)
+    }
+
+    pub fn erc20(&self) -> MockErc20 {
+        MockErc20 {
+            vm: self.vm.clone(),
+            address: TOKEN,
+        }
+    }
+
+    /// Executes a scheduled operation as if its timelock delay had passed, leaving the clock
+    /// where it was.
+    pub fn execute_after_delay(&mut self, operation_id: U256) {
+        let now = self.vm.block_timestamp();
+        let (_, eta, _) = self.contract.get_operation(operation_id);
+        self.vm.set_block_timestamp(eta);
+        self.contract
+            .execute_operation(operation_id)
+            .expect("execute_operation");
+        self.vm.set_block_timestamp(now);
+    }
+
+    /// Configures `REGISTRY` as the contract's ArbiterRegistry and returns its mock.
+    pub fn registry(&mut self) -> MockRegistry {
+        self.sender(ADMIN);
+        let operation_id = self.contract.schedule_arbiter_registry(REGISTRY).unwrap();
+        self.execute_after_delay(operation_id);
+        MockRegistry {
+            vm: self.vm.clone(),
+        }
+    }
+
+    /// Configures the dispute bond for `token` deals through the timelock.
+    pub fn dispute_bond(&mut self, token: Address, amount: U256, bps: U256) {
+        self.sender(ADMIN);
+        let operation_id = self
+            .contract
+            .schedule_dispute_bond(token, amount, bps)
+            .unwrap();
+        self.execute_after_delay(operation_id);
+    }
+
+    /// Creates an ETH deal from `CLIENT` to `FREELANCER` with `ARBITER`, one milestone per
+    /// amount, no time locks and no approval requirement.
+    pub fn eth_deal(&mut self, amounts: &[u64]) -> U256 {
+        self.deal_with(ARBITER, Address::ZERO, amounts, Vec::new())
+    }
+
+    /// Creates an ETH deal from `CLIENT` to `FREELANCER` with `ARBITER` and a single milestone
+    /// streaming `amount` from `start` to `end`.
+    pub fn stream_deal(&mut self, amount: u64, start: u64, end: u64) -> U256 {
+        self.sender(CLIENT).value(u(amount));
+        let deal_id = self
+            .contract
+            .create_deal_with_terms(
+                U256::ZERO,
+                FREELANCER,
+                ARBITER,
+                Address::ZERO,
+                u(amount),
+                vec![u(amount)],
+                vec![u(end)],
+                vec![u(start)],
+                vec![U256::ZERO],
+                Vec::new(),
+                PROPORTIONAL,
+            )
+            .expect("create_deal");
+        self.vm.set_value(U256::ZERO);
+        deal_id
+    }
+
+    /// Opens an ETH retainer from `CLIENT` to `FREELANCER` with `ARBITER`, paying
+    /// `period_amount` every `PERIOD` seconds and pre-funding `periods` periods.
+    pub fn retainer(&mut self, period_amount: u64, periods: u64, notice_periods: u64) -> U256 {
+        self.sender(CLIENT).value(u(period_amount * periods));
+        let deal_id = self
+            .contract
+            .create_retainer(
+                FREELANCER,
+                ARBITER,
+                Address::ZERO,
+                u(period_amount),
+                PERIOD,
+                periods,
+                notice_periods,
+                Vec::new(),
+                PROPORTIONAL,
+            )
+            .expect("create_retainer");
+        self.vm.set_value(U256::ZERO);
+        deal_id
+    }
+
+    /// Creates a deal from `CLIENT` to `FREELANCER` in `token` with the given arbiter or panel.
+    pub fn deal_with(
+        &mut self,
+        arbiter: Address,
+        token: Address,
+        amounts: &[u64],
+        panel: Vec<Address>,
+    ) -> U256 {
+        self.deal_with_fee_mode(arbiter, token, amounts, panel, PROPORTIONAL)
+    }
+
+    /// Like `deal_with`, allocating the arbiter fee according to `fee_mode`.
+    pub fn deal_with_fee_mode(
+        &mut self,
+        arbiter: Address,
+        token: Address,
+        amounts: &[u64],
+        panel: Vec<Address>,
+        fee_mode: u8,
+    ) -> U256 {
+        let total: u64 = amounts.iter().sum();
+        self.sender(CLIENT);
+        if token == Address::ZERO {
+            self.value(u(total));
+        } else {
+            self.erc20().expect_transfer_from(CLIENT, ESCROW, u(total));
+        }
+
+        let deal_id = self
+            .contract
+            .create_deal_with_terms(
+                U256::ZERO,
+                FREELANCER,
+                arbiter,
+                token,
+                u(total),
+                amounts.iter().map(|a| u(*a)).collect(),
+                vec![U256::ZERO; amounts.len()],
+                vec![U256::ZERO; amounts.len()],
+                vec![U256::ZERO; amounts.len()],
+                panel,
+                fee_mode,
+            )
+            .expect("create_deal");
+        self.vm.set_value(U256::ZERO);
+        deal_id
+    }
+
+    pub fn status(&self, deal_id: U256) -> u64 {
+        self.contract.get_deal_status(deal_id).to::<u64>()
+    }
+
+    /// Decodes every emitted log of event type `E`.
+    pub fn events<E: SolEvent>(&self) -> Vec<E> {
+        self.vm
+            .get_emitted_logs()
+            .into_iter()
+            .filter(|(topics, _)| topics.first() == Some(&E::SIGNATURE_HASH))
+            .map(|(topics, data)| E::decode_raw_log(topics, &data, true).expect("decode"))
+            .collect()
+    }
+}
+
+/// Call-level mock of an ERC20 token deployed at `address`
+pub struct MockErc20 {
+    vm: TestVM,
+    pub address: Address,
+}
+
+impl MockErc20 {
+    pub fn expect_transfer_from(&self, from: Address, to: Address, amount: U256) {
+        let call = IERC20::transferFromCall {
+            sender: from,
+            recipient: to,
+            amount,
+        };
+        self.vm
+            .mock_call(self.address, call.abi_encode(), Ok(true.abi_encode()));
+    }
+
+    pub fn reject_transfer_from(&self, from: Address, to: Address, amount: U256) {
+        let call = IERC20::transferFromCall {
+            sender: from,
+            recipient: to,
+            amount,
+        };
+        self.vm
+            .mock_call(self.address, call.abi_encode(), Ok(false.abi_encode()));
+    }
+
+    pub fn expect_transfer(&self, to: Address, amount: U256) {
+        let call = IERC20::transferCall {
+            recipient: to,
+            amount,
+        };
+        self.vm
+            .mock_call(self.address, call.abi_encode(), Ok(true.abi_encode()));
+    }
+
+    pub fn reject_transfer(&self, to: Address, amount: U256) {
+        let call = IERC20::transferCall {
+            recipient: to,
+            amount,
+        };
+        self.vm
+            .mock_call(self.address, call.abi_encode(), Ok(false.abi_encode()));
+    }
+
+    pub fn set_balance(&self, account: Address, balance: U256) {
+        let call = IERC20::balanceOfCall { account };
+        self.vm
+            .mock_static_call(self.address, call.abi_encode(), Ok(balance.abi_encode()));
+    }
+}
+
+/// Call-level mock of the ArbiterRegistry deployed at `REGISTRY`
+pub struct MockRegistry {
+    vm: TestVM,
+}
+
+impl MockRegistry {
+    pub fn expect_record(&self, arbiter: Address, deal_id: U256, amount: U256) {
+        let call = IArbiterRegistry::recordResolutionCall {
+            arbiter,
+            deal_id,
+            amount,
+        };
+        self.vm
+            .mock_call(REGISTRY, call.abi_encode(), Ok(Vec::new()));
+    }
+
+    pub fn reject_record(&self, arbiter: Address, deal_id: U256, amount: U256) {
+        let call = IArbiterRegistry::recordResolutionCall {
+            arbiter,
+            deal_id,
+            amount,
+        };
+        self.vm
+            .mock_call(REGISTRY, call.abi_encode(), Err(b"Escrow".to_vec()));
+    }
+
+    pub fn set_status(&self, arbiter: Address, is_active: bool) {
+        let call = IArbiterRegistry::getArbiterStatusCall { arbiter };
+        let status = (is_active, U256::from(1000), U256::from(100));
+        self.vm
+            .mock_static_call(REGISTRY, call.abi_encode(), Ok(status.abi_encode()));
+    }
+
+    pub fn select(&self, seed: U256, exclude: Vec<Address>, arbiter: Address) {
+        let call = IArbiterRegistry::selectArbiterCall { seed, exclude };
+        self.vm
+            .mock_static_call(REGISTRY, call.abi_encode(), Ok(arbiter.abi_encode()));
+    }
+}
+
+/// Seed `ArbiSecure` derives from the parties' revealed secrets.
+pub fn selection_seed(client_secret: U256, freelancer_secret: U256, deal_id: U256) -> U256 {
+    let mut data = (client_secret ^ freelancer_secret)
+        .to_be_bytes::<32>()
+        .to_vec();
+    data.extend_from_slice(&deal_id.to_be_bytes::<32>());
+    U256::from_be_bytes(alloy_primitives::keccak256(&data).0)
+}
+
+/// Expected arbiter seed commitment, mirroring `arbiter_seed_commitment`.
+pub fn commitment(secret: U256, party: Address) -> B256 {
+    let mut data = secret.to_be_bytes::<32>().to_vec();
+    data.extend_from_slice(party.as_slice());
+    alloy_primitives::keccak256(&data)
+}
@@ -0,0 +1,2090 @@
+extern crate alloc;
+
+mod common;
+
+use alloy_primitives::{Address, U256};
+use arbisecure_contracts::{
+    arbiter_seed_commitment, AdminTransferred, ArbiterReassigned, DealCreated, DisputeBondPosted,
+    DisputeBondsReleased, DisputeResolved, DisputeWithdrawn, Initialized, Milestone,
+    MilestoneReleased, Paused, ResolutionReportFailed, RetainerEnded, RoleGranted, RulingIssued,
+    SettlementAccepted, StreamCancelled, StreamClaimed, APPEAL_RULING_WINDOW, DEFAULT_ADMIN_ROLE,
+    FEE_MANAGER_ROLE, PAUSER_ROLE, SEED_REVEAL_WINDOW, STORAGE_VERSION, TOKEN_LISTER_ROLE,
+};
+use common::*;
+use stylus_sdk::{prelude::*, storage::StorageType};
+
+// ============================================================================
+// Admin
+// ============================================================================
+
+#[test]
+fn constructor_sets_admin() {
+    let escrow = Escrow::new();
+    assert_eq!(escrow.contract.admin(), ADMIN);
+
+    let initialized = escrow.events::<Initialized>();
+    assert_eq!(
+        (initialized[0].admin, initialized[0].storage_version),
+        (ADMIN, STORAGE_VERSION)
+    );
+}
+
+#[test]
+#[should_panic]
+fn initialize_rejects_initialized_storage() {
+    let mut escrow = Escrow::new();
+    escrow.sender(STRANGER);
+    escrow.contract.initialize(STRANGER);
+}
+
+#[test]
+fn admin_transfer_takes_effect_on_acceptance() {
+    let mut escrow = Escrow::new();
+    escrow.sender(ADMIN);
+    escrow.contract.propose_admin(STRANGER);
+    assert_eq!(escrow.contract.pending_admin(), STRANGER);
+    // The current admin stays in control until the nominee accepts
+    assert_eq!(escrow.contract.admin(), ADMIN);
+    escrow.contract.schedule_arbiter_registry(REGISTRY).unwrap();
+
+    escrow.sender(STRANGER);
+    escrow.contract.accept_admin();
+    assert_eq!(escrow.contract.admin(), STRANGER);
+    assert_eq!(escrow.contract.pending_admin(), Address::ZERO);
+    assert!(escrow.contract.has_role(DEFAULT_ADMIN_ROLE, STRANGER));
+    assert!(!escrow.contract.has_role(DEFAULT_ADMIN_ROLE, ADMIN));
+
+    let transferred = escrow.events::<AdminTransferred>();
+    assert_eq!(
+        (transferred[0].previous_admin, transferred[0].new_admin),
+        (ADMIN, STRANGER)
+    );
+}
+
+#[test]
+#[should_panic]
+fn propose_admin_requires_admin() {
+    let mut escrow = Escrow::new();
+    escrow.sender(STRANGER);
+    escrow.contract.propose_admin(STRANGER);
+}
+
+#[test]
+#[should_panic]
+fn accept_admin_requires_nominee() {
+    let mut escrow = Escrow::new();
+    escrow.sender(ADMIN);
+    escrow.contract.propose_admin(FREELANCER);
+
+    escrow.sender(STRANGER);
+    escrow.contract.accept_admin();
+}
+
+#[test]
+#[should_panic]
+fn cancelled_nomination_cannot_be_accepted() {
+    let mut escrow = Escrow::new();
+    escrow.sender(ADMIN);
+    escrow.contract.propose_admin(STRANGER);
+    escrow.contract.cancel_admin_proposal();
+    assert_eq!(escrow.contract.pending_admin(), Address::ZERO);
+
+    escrow.sender(STRANGER);
+    escrow.contract.accept_admin();
+}
+
+#[test]
+fn admin_configures_registry_and_appeals() {
+    let mut escrow = Escrow::new();
+    escrow.registry();
+    assert_eq!(escrow.contract.arbiter_registry(), REGISTRY);
+
+    escrow.sender(ADMIN);
+    let operation_id = escrow
+        .contract
+        .schedule_appeal_config(u(3600), u(1000), COURT)
+        .unwrap();
+    escrow.execute_after_delay(operation_id);
+    assert_eq!(
+        escrow.contract.get_appeal_config(),
+        (u(3600), u(1000), COURT)
+    );
+}
+
+#[test]
+#[should_panic]
+fn appeal_config_requires_admin() {
+    let mut escrow = Escrow::new();
+    escrow.sender(STRANGER);
+    escrow
+        .contract
+        .schedule_appeal_config(u(3600), u(1000), COURT)
+        .unwrap();
+}
+
+// ============================================================================
+// Roles
+// ============================================================================
+
+#[test]
+fn initialize_grants_operational_roles_to_admin() {
+    let escrow = Escrow::new();
+    for role in [
+        DEFAULT_ADMIN_ROLE,
+        FEE_MANAGER_ROLE,
+        PAUSER_ROLE,
+        TOKEN_LISTER_ROLE,
+    ] {
+        assert!(escrow.contract.has_role(role, ADMIN));
+        assert!(!escrow.contract.has_role(role, STRANGER));
+    }
+}
+
+#[test]
+fn fee_manager_withdraws_without_admin_key() {
+    let mut escrow = Escrow::new();
+    escrow.sender(ADMIN);
+    escrow.contract.grant_role(FEE_MANAGER_ROLE, STRANGER);
+    assert!(escrow.contract.has_role(FEE_MANAGER_ROLE, STRANGER));
+
+    let granted = escrow.events::<RoleGranted>();
+    let last = granted.last().unwrap();
+    assert_eq!(
+        (last.role, last.account, last.sender),
+        (FEE_MANAGER_ROLE, STRANGER, ADMIN)
+    );
+
+    let deal_id = escrow.eth_deal(&[1000]);
+    escrow.sender(CLIENT);
+    escrow.contract.release_milestone(deal_id, u(0)).unwrap();
+    assert_eq!(escrow.eth_balance(FREELANCER), u(995));
+
+    escrow.sender(STRANGER);
+    escrow
+        .contract
+        .withdraw_fees(Address::ZERO, STRANGER)
+        .unwrap();
+    assert_eq!(escrow.eth_balance(STRANGER), u(5));
+    assert_eq!(escrow.contract.get_accrued_fees(Address::ZERO), U256::ZERO);
+}
+
+#[test]
+#[should_panic]
+fn revoked_role_loses_access() {
+    let mut escrow = Escrow::new();
+    escrow.sender(ADMIN);
+    escrow.contract.grant_role(PAUSER_ROLE, STRANGER);
+    escrow.contract.revoke_role(PAUSER_ROLE, STRANGER);
+    assert!(!escrow.contract.has_role(PAUSER_ROLE, STRANGER));
+
+    escrow.sender(STRANGER);
+    escrow.contract.pause();
+}
+
+#[test]
+#[should_panic]
+fn grant_role_requires_admin() {
+    let mut escrow = Escrow::new();
+    escrow.sender(STRANGER);
+    escrow.contract.grant_role(PAUSER_ROLE, STRANGER);
+}
+
+#[test]
+#[should_panic]
+fn default_admin_role_moves_only_by_transfer() {
+    let mut escrow = Escrow::new();
+    escrow.sender(ADMIN);
+    escrow.contract.grant_role(DEFAULT_ADMIN_ROLE, STRANGER);
+}
+
+#[test]
+fn pauser_pauses_without_admin_key() {
+    let mut escrow = Escrow::new();
+    escrow.sender(ADMIN);
+    escrow.contract.grant_role(PAUSER_ROLE, STRANGER);
+    escrow.contract.revoke_role(PAUSER_ROLE, ADMIN);
+    let deal_id = escrow.eth_deal(&[1000]);
+    escrow.sender(CLIENT);
+    escrow.contract.raise_dispute(deal_id).unwrap();
+
+    escrow.sender(STRANGER);
+    escrow.contract.pause();
+    assert!(escrow.contract.paused());
+    assert_eq!(escrow.events::<Paused>()[0].account, STRANGER);
+    escrow.contract.unpause();
+    assert!(!escrow.contract.paused());
+
+    escrow.sender(ARBITER);
+    escrow
+        .contract
+        .resolve_dispute(deal_id, u(1000), U256::ZERO)
+        .unwrap();
+    assert_eq!(escrow.eth_balance(CLIENT), u(950));
+    assert_eq!(escrow.eth_balance(ARBITER), u(50));
+    assert_eq!(escrow.status(deal_id), COMPLETED);
+}
+
+#[test]
+#[should_panic]
+fn pause_blocks_deal_creation() {
+    let mut escrow = Escrow::new();
+    escrow.sender(ADMIN);
+    escrow.contract.pause();
+    escrow.eth_deal(&[1000]);
+}
+
+#[test]
+#[should_panic]
+fn pause_blocks_releases() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.eth_deal(&[1000]);
+    escrow.sender(ADMIN);
+    escrow.contract.pause();
+
+    escrow.sender(CLIENT);
+    let _ = escrow.contract.release_milestone(deal_id, u(0));
+}
+
+#[test]
+#[should_panic]
+fn pause_blocks_disputes() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.eth_deal(&[1000]);
+    escrow.sender(ADMIN);
+    escrow.contract.pause();
+
+    escrow.sender(CLIENT);
+    let _ = escrow.contract.raise_dispute(deal_id);
+}
+
+#[test]
+#[should_panic]
+fn pause_blocks_bond_matching() {
+    let mut escrow = Escrow::new();
+    escrow.dispute_bond(Address::ZERO, u(100), U256::ZERO);
+    let deal_id = escrow.eth_deal(&[1000]);
+    escrow.sender(CLIENT).value(u(100));
+    escrow.contract.raise_dispute(deal_id).unwrap();
+    escrow.sender(ADMIN);
+    escrow.contract.pause();
+
+    escrow.sender(FREELANCER).value(u(100));
+    let _ = escrow.contract.match_dispute_bond(deal_id);
+}
+
+#[test]
+#[should_panic]
+fn pause_requires_pauser() {
+    let mut escrow = Escrow::new();
+    escrow.sender(STRANGER);
+    escrow.contract.pause();
+}
+
+#[test]
+#[should_panic]
+fn unlisted_token_is_rejected() {
+    let mut escrow = Escrow::new();
+    escrow.sender(ADMIN);
+    let operation_id = escrow
+        .contract
+        .schedule_token_allowed(TOKEN, false)
+        .unwrap();
+    escrow.execute_after_delay(operation_id);
+    assert!(!escrow.contract.is_token_allowed(TOKEN));
+    assert!(escrow.contract.is_token_allowed(Address::ZERO));
+
+    escrow.deal_with(ARBITER, TOKEN, &[250], Vec::new());
+}
+
+#[test]
+#[should_panic]
+fn token_listing_requires_lister() {
+    let mut escrow = Escrow::new();
+    escrow.sender(STRANGER);
+    escrow
+        .contract
+        .schedule_token_allowed(STRANGER, true)
+        .unwrap();
+}
+
+// ============================================================================
+// Deal creation
+// ============================================================================
+
+#[test]
+fn create_eth_deal_escrows_funds() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.eth_deal(&[600, 400]);
+
+    assert_eq!(deal_id, U256::ZERO);
+    assert_eq!(escrow.status(deal_id), FUNDED);
+    assert_eq!(escrow.contract.get_deal_client(deal_id), CLIENT);
+    assert_eq!(escrow.contract.get_deal_freelancer(deal_id), FREELANCER);
+    assert_eq!(escrow.contract.get_deal_arbiter(deal_id), ARBITER);
+    assert_eq!(escrow.contract.get_deal_amount(deal_id), u(1000));
+    assert_eq!(escrow.contract.get_total_escrowed(Address::ZERO), u(1000));
+    assert_eq!(
+        escrow.contract.get_milestone(deal_id, u(1)).unwrap(),
+        (u(400), false, U256::ZERO, false)
+    );
+    assert!(escrow.contract.get_milestone(deal_id, u(2)).is_err());
+
+    let created = escrow.events::<DealCreated>();
+    assert_eq!(created.len(), 1);
+    assert_eq!(created[0].amount, u(1000));
+}
+
+#[test]
+fn create_erc20_deal_pulls_tokens() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.deal_with(ARBITER, TOKEN, &[250], Vec::new());
+
+    assert_eq!(escrow.status(deal_id), FUNDED);
+    assert_eq!(escrow.contract.get_total_escrowed(TOKEN), u(250));
+}
+
+#[test]
+#[should_panic]
+fn create_deal_rejects_failed_token_pull() {
+    let mut escrow = Escrow::new();
+    escrow.erc20().reject_transfer_from(CLIENT, ESCROW, u(100));
+    escrow.sender(CLIENT);
+    let _ = escrow.contract.create_deal(
+        U256::ZERO,
+        FREELANCER,
+        ARBITER,
+        TOKEN,
+        u(100),
+        vec![u(100)],
+        vec![U256::ZERO],
+        vec![U256::ZERO],
+    );
+}
+
+#[test]
+#[should_panic]
+fn create_deal_rejects_wrong_eth_value() {
+    let mut escrow = Escrow::new();
+    escrow.sender(CLIENT).value(u(99));
+    let _ = escrow.contract.create_deal(
+        U256::ZERO,
+        FREELANCER,
+        ARBITER,
+        Address::ZERO,
+        u(100),
+        vec![u(100)],
+        vec![U256::ZERO],
+        vec![U256::ZERO],
+    );
+}
+
+#[test]
+#[should_panic]
+fn create_deal_rejects_milestone_sum_mismatch() {
+    let mut escrow = Escrow::new();
+    escrow.sender(CLIENT).value(u(100));
+    let _ = escrow.contract.create_deal(
+        U256::ZERO,
+        FREELANCER,
+        ARBITER,
+        Address::ZERO,
+        u(100),
+        vec![u(60), u(30)],
+        vec![U256::ZERO; 2],
+        vec![U256::ZERO; 2],
+    );
+}
+
+#[test]
+fn create_deal_reverts_on_wrapped_milestone_sum() {
+    let mut escrow = Escrow::new();
+    escrow.sender(CLIENT).value(u(1));
+    let result = escrow.contract.create_deal(
+        U256::ZERO,
+        FREELANCER,
+        ARBITER,
+        Address::ZERO,
+        u(1),
+        vec![U256::MAX, u(2)],
+        vec![U256::ZERO; 2],
+        vec![U256::ZERO; 2],
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+#[should_panic]
+fn create_deal_rejects_end_time_beyond_u64() {
+    let mut escrow = Escrow::new();
+    escrow.sender(CLIENT).value(u(1));
+    let _ = escrow.contract.create_deal(
+        U256::ZERO,
+        FREELANCER,
+        ARBITER,
+        Address::ZERO,
+        u(1),
+        vec![u(1)],
+        vec![U256::from(u64::MAX) + u(1)],
+        vec![U256::ZERO],
+    );
+}
+
+sol_storage! {
+    /// `Milestone` as it was laid out before storage packing
+    pub struct UnpackedMilestone {
+        uint256 amount;
+        bool is_released;
+        uint256 end_timestamp;
+        bool requires_approval;
+    }
+}
+
+#[test]
+fn milestone_fits_in_two_slots() {
+    assert_eq!(<UnpackedMilestone as StorageType>::REQUIRED_SLOTS, 4);
+    assert_eq!(<Milestone as StorageType>::REQUIRED_SLOTS, 2);
+}
+
+// ============================================================================
+// Milestone release
+// ============================================================================
+
+#[test]
+fn client_release_pays_freelancer_minus_fee() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.eth_deal(&[1000, 1000]);
+
+    escrow.sender(CLIENT);
+    escrow.contract.release_milestone(deal_id, u(0)).unwrap();
+    assert_eq!(escrow.eth_balance(FREELANCER), u(995));
+
+    assert_eq!(escrow.status(deal_id), ACTIVE);
+    assert_eq!(escrow.contract.get_deal_amount(deal_id), u(1000));
+    assert_eq!(escrow.contract.get_accrued_fees(Address::ZERO), u(5));
+    assert_eq!(escrow.contract.get_total_escrowed(Address::ZERO), u(1000));
+    assert_eq!(escrow.events::<MilestoneReleased>()[0].amount, u(995));
+
+    escrow.sender(CLIENT);
+    escrow.contract.release_milestone(deal_id, u(1)).unwrap();
+    assert_eq!(escrow.status(deal_id), COMPLETED);
+}
+
+#[test]
+#[should_panic]
+fn milestone_cannot_be_released_twice() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.eth_deal(&[1000, 1000]);
+
+    escrow.sender(CLIENT);
+    escrow.contract.release_milestone(deal_id, u(0)).unwrap();
+    let _ = escrow.contract.release_milestone(deal_id, u(0));
+}
+
+#[test]
+fn freelancer_claims_after_time_lock() {
+    let mut escrow = Escrow::new();
+    escrow.sender(CLIENT).value(u(1000));
+    let deal_id = escrow
+        .contract
+        .create_deal(
+            U256::ZERO,
+            FREELANCER,
+            ARBITER,
+            Address::ZERO,
+            u(1000),
+            vec![u(1000)],
+            vec![u(5_000)],
+            vec![U256::ZERO],
+        )
+        .unwrap();
+
+    escrow.sender(FREELANCER).at(5_000);
+    escrow.contract.release_milestone(deal_id, u(0)).unwrap();
+    assert_eq!(escrow.eth_balance(FREELANCER), u(995));
+    assert_eq!(escrow.status(deal_id), COMPLETED);
+}
+
+#[test]
+#[should_panic]
+fn freelancer_cannot_claim_before_time_lock() {
+    let mut escrow = Escrow::new();
+    escrow.sender(CLIENT).value(u(1000));
+    let deal_id = escrow
+        .contract
+        .create_deal(
+            U256::ZERO,
+            FREELANCER,
+            ARBITER,
+            Address::ZERO,
+            u(1000),
+            vec![u(1000)],
+            vec![u(5_000)],
+            vec![U256::ZERO],
+        )
+        .unwrap();
+
+    escrow.sender(FREELANCER).at(4_999);
+    let _ = escrow.contract.release_milestone(deal_id, u(0));
+}
+
+#[test]
+#[should_panic]
+fn freelancer_cannot_claim_approval_milestone() {
+    let mut escrow = Escrow::new();
+    escrow.sender(CLIENT).value(u(1000));
+    let deal_id = escrow
+        .contract
+        .create_deal(
+            U256::ZERO,
+            FREELANCER,
+            ARBITER,
+            Address::ZERO,
+            u(1000),
+            vec![u(1000)],
+            vec![U256::ZERO],
+            vec![u(1)],
+        )
+        .unwrap();
+
+    escrow.sender(FREELANCER);
+    let _ = escrow.contract.release_milestone(deal_id, u(0));
+}
+
+#[test]
+fn erc20_release_transfers_tokens() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.deal_with(ARBITER, TOKEN, &[2000], Vec::new());
+
+    escrow.erc20().expect_transfer(FREELANCER, u(1990));
+    escrow.sender(CLIENT);
+    escrow.contract.release_milestone(deal_id, u(0)).unwrap();
+    assert_eq!(escrow.contract.get_accrued_fees(TOKEN), u(10));
+    assert_eq!(escrow.contract.get_total_escrowed(TOKEN), U256::ZERO);
+}
+
+#[test]
+fn failed_token_transfer_reverts_release() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.deal_with(ARBITER, TOKEN, &[2000], Vec::new());
+
+    escrow.erc20().reject_transfer(FREELANCER, u(1990));
+    escrow.sender(CLIENT);
+    assert!(escrow.contract.release_milestone(deal_id, u(0)).is_err());
+}
+
+// ============================================================================
+// Streaming milestones
+// ============================================================================
+
+#[test]
+fn stream_vests_linearly() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.stream_deal(1000, 1000, 2000);
+
+    escrow.at(1250);
+    assert_eq!(
+        escrow.contract.get_stream(deal_id, u(0)).unwrap(),
+        (u(1000), u(2000), u(250))
+    );
+    escrow.sender(FREELANCER);
+    escrow.contract.claim_streamed(deal_id, u(0)).unwrap();
+    assert_eq!(escrow.eth_balance(FREELANCER), u(249));
+    assert_eq!(escrow.status(deal_id), ACTIVE);
+    assert_eq!(escrow.contract.get_deal_amount(deal_id), u(750));
+
+    // The remainder keeps vesting at the same rate
+    escrow.at(1500);
+    assert_eq!(
+        escrow.contract.get_stream(deal_id, u(0)).unwrap(),
+        (u(1250), u(2000), u(250))
+    );
+
+    escrow.at(2500);
+    escrow.contract.claim_streamed(deal_id, u(0)).unwrap();
+    assert_eq!(escrow.eth_balance(FREELANCER), u(996));
+    assert_eq!(escrow.status(deal_id), COMPLETED);
+    assert!(escrow.contract.get_milestone(deal_id, u(0)).unwrap().1);
+    assert_eq!(escrow.contract.get_accrued_fees(Address::ZERO), u(4));
+    assert_eq!(
+        escrow.contract.get_total_escrowed(Address::ZERO),
+        U256::ZERO
+    );
+}
+
+#[test]
+#[should_panic]
+fn nothing_to_claim_before_the_stream_starts() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.stream_deal(1000, 1500, 2000);
+
+    escrow.sender(FREELANCER);
+    let _ = escrow.contract.claim_streamed(deal_id, u(0));
+}
+
+#[test]
+#[should_panic]
+fn only_the_freelancer_claims_a_stream() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.stream_deal(1000, 1000, 2000);
+
+    escrow.at(1500).sender(CLIENT);
+    let _ = escrow.contract.claim_streamed(deal_id, u(0));
+}
+
+#[test]
+#[should_panic]
+fn disputed_stream_cannot_be_claimed() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.stream_deal(1000, 1000, 2000);
+
+    escrow.at(1500).sender(CLIENT);
+    escrow.contract.raise_dispute(deal_id).unwrap();
+
+    escrow.sender(FREELANCER);
+    let _ = escrow.contract.claim_streamed(deal_id, u(0));
+}
+
+#[test]
+fn dispute_freezes_only_the_unvested_stream() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.stream_deal(1000, 1000, 2000);
+
+    escrow.at(1250);
+    escrow.sender(FREELANCER);
+    escrow.contract.claim_streamed(deal_id, u(0)).unwrap();
+    assert_eq!(escrow.eth_balance(FREELANCER), u(249));
+
+    // The 250 vested since the claim is paid out; only the unvested 500 is disputed
+    escrow.at(1500).sender(CLIENT);
+    escrow.contract.raise_dispute(deal_id).unwrap();
+    assert_eq!(escrow.eth_balance(FREELANCER), u(249 + 249));
+    assert_eq!(escrow.events::<StreamClaimed>()[1].amount, u(249));
+    assert_eq!(escrow.contract.get_disputed_amount(deal_id), u(500));
+    assert_eq!(escrow.contract.get_deal_amount(deal_id), u(500));
+
+    escrow.sender(ARBITER);
+    escrow
+        .contract
+        .resolve_dispute(deal_id, u(500), U256::ZERO)
+        .unwrap();
+    assert_eq!(escrow.eth_balance(CLIENT), u(475));
+    assert_eq!(escrow.eth_balance(ARBITER), u(25));
+    assert_eq!(escrow.status(deal_id), COMPLETED);
+}
+
+#[test]
+#[should_panic]
+fn fully_vested_stream_cannot_be_disputed() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.stream_deal(1000, 1000, 2000);
+
+    escrow.at(2000).sender(CLIENT);
+    let _ = escrow.contract.raise_dispute(deal_id);
+}
+
+#[test]
+fn withdrawn_dispute_resumes_the_stream_where_it_paused() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.stream_deal(1000, 1000, 2000);
+
+    // The vested 400 is paid out when the dispute is raised
+    escrow.at(1400).sender(CLIENT);
+    escrow.contract.raise_dispute(deal_id).unwrap();
+    assert_eq!(escrow.eth_balance(FREELANCER), u(398));
+
+    // The remaining 600 picks up vesting over the rest of the window, shifted by the pause
+    escrow.at(1600);
+    escrow.contract.withdraw_dispute(deal_id).unwrap();
+    assert_eq!(
+        escrow.contract.get_stream(deal_id, u(0)).unwrap(),
+        (u(1600), u(2200), U256::ZERO)
+    );
+    escrow.at(1900);
+    assert_eq!(escrow.contract.get_stream(deal_id, u(0)).unwrap().2, u(300));
+}
+
+#[test]
+fn cancelled_stream_pays_vested_and_refunds_the_rest() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.stream_deal(1000, 1000, 2000);
+
+    escrow.at(1400).sender(CLIENT);
+    escrow.contract.cancel_stream(deal_id, u(0)).unwrap();
+    assert_eq!(escrow.eth_balance(FREELANCER), u(398));
+    assert_eq!(escrow.eth_balance(CLIENT), u(600));
+    assert_eq!(escrow.events::<StreamCancelled>()[0].refund, u(600));
+    assert_eq!(escrow.status(deal_id), COMPLETED);
+    assert_eq!(
+        escrow.contract.get_total_escrowed(Address::ZERO),
+        U256::ZERO
+    );
+}
+
+#[test]
+#[should_panic]
+fn disputed_stream_cannot_be_cancelled() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.stream_deal(1000, 1000, 2000);
+
+    escrow.at(1500).sender(CLIENT);
+    escrow.contract.raise_dispute(deal_id).unwrap();
+    let _ = escrow.contract.cancel_stream(deal_id, u(0));
+}
+
+#[test]
+#[should_panic]
+fn stream_must_end_after_it_starts() {
+    let mut escrow = Escrow::new();
+    escrow.stream_deal(1000, 2000, 2000);
+}
+
+// ============================================================================
+// Retainers
+// ============================================================================
+
+#[test]
+fn retainer_funds_one_milestone_per_period() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.retainer(100, 3, 1);
+
+    assert_eq!(
+        escrow.contract.get_retainer(deal_id),
+        (u(100), PERIOD, 1, 4000, 0)
+    );
+    assert_eq!(escrow.contract.get_deal_amount(deal_id), u(300));
+    assert_eq!(
+        escrow.contract.get_milestone(deal_id, u(2)).unwrap(),
+        (u(100), false, u(4000), false)
+    );
+
+    escrow.at(2000);
+    escrow.sender(FREELANCER);
+    escrow.contract.release_milestone(deal_id, u(0)).unwrap();
+    assert_eq!(escrow.eth_balance(FREELANCER), u(100));
+    assert_eq!(escrow.status(deal_id), ACTIVE);
+}
+
+#[test]
+fn retainer_stays_open_between_periods() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.retainer(100, 1, 0);
+
+    escrow.at(2000);
+    escrow.sender(FREELANCER);
+    escrow.contract.release_milestone(deal_id, u(0)).unwrap();
+    assert_eq!(escrow.eth_balance(FREELANCER), u(100));
+    assert_eq!(escrow.status(deal_id), ACTIVE);
+    assert_eq!(escrow.contract.get_deal_amount(deal_id), U256::ZERO);
+
+    // Re-funding after a gap starts the next period now
+    escrow.at(2500).sender(CLIENT).value(u(200));
+    escrow.contract.fund_retainer(deal_id, 2).unwrap();
+    assert_eq!(escrow.contract.get_retainer(deal_id).3, 4500);
+    assert_eq!(
+        escrow.contract.get_milestone(deal_id, u(1)).unwrap(),
+        (u(100), false, u(3500), false)
+    );
+    assert_eq!(escrow.contract.get_deal_amount(deal_id), u(200));
+}
+
+#[test]
+fn ending_refunds_periods_after_the_notice() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.retainer(100, 4, 1);
+
+    // The current period ends at 2000 and the notice period at 3000
+    escrow.at(1500);
+    escrow.sender(FREELANCER);
+    escrow.contract.end_retainer(deal_id).unwrap();
+    assert_eq!(escrow.eth_balance(CLIENT), u(200));
+
+    let ended = &escrow.events::<RetainerEnded>()[0];
+    assert_eq!((ended.ends_at, ended.refund), (3000, u(200)));
+    assert_eq!(escrow.contract.get_deal_amount(deal_id), u(200));
+    assert_eq!(escrow.contract.get_total_escrowed(Address::ZERO), u(200));
+
+    // The notice periods are paid as usual, and the deal closes after the last one
+    escrow.at(3000);
+    escrow.contract.release_milestone(deal_id, u(0)).unwrap();
+    assert_eq!(escrow.eth_balance(FREELANCER), u(100));
+    escrow.contract.release_milestone(deal_id, u(1)).unwrap();
+    assert_eq!(escrow.status(deal_id), COMPLETED);
+}
+
+#[test]
+#[should_panic]
+fn ended_retainer_cannot_be_topped_up() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.retainer(100, 2, 0);
+
+    escrow.sender(CLIENT);
+    escrow.contract.end_retainer(deal_id).unwrap();
+
+    escrow.value(u(100));
+    let _ = escrow.contract.fund_retainer(deal_id, 1);
+}
+
+#[test]
+#[should_panic]
+fn only_the_client_funds_a_retainer() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.retainer(100, 1, 0);
+
+    escrow.sender(FREELANCER).value(u(100));
+    let _ = escrow.contract.fund_retainer(deal_id, 1);
+}
+
+// ============================================================================
+// Fees and solvency
+// ============================================================================
+
+#[test]
+fn admin_withdraws_accrued_fees() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.eth_deal(&[1000]);
+    escrow.sender(CLIENT);
+    escrow.contract.release_milestone(deal_id, u(0)).unwrap();
+    assert_eq!(escrow.eth_balance(FREELANCER), u(995));
+
+    escrow.sender(ADMIN);
+    escrow.contract.withdraw_fees(Address::ZERO, ADMIN).unwrap();
+    assert_eq!(escrow.eth_balance(ADMIN), u(5));
+    assert_eq!(escrow.contract.get_accrued_fees(Address::ZERO), U256::ZERO);
+}
+
+#[test]
+#[should_panic]
+fn withdraw_fees_requires_fee_manager() {
+    let mut escrow = Escrow::new();
+    escrow.sender(STRANGER);
+    let _ = escrow.contract.withdraw_fees(Address::ZERO, STRANGER);
+}
+
+#[test]
+fn solvency_compares_balance_with_obligations() {
+    let mut escrow = Escrow::new();
+    escrow.deal_with(ARBITER, TOKEN, &[500], Vec::new());
+
+    escrow.erc20().set_balance(ESCROW, u(500));
+    assert!(escrow.contract.is_solvent(TOKEN).unwrap());
+
+    escrow.erc20().set_balance(ESCROW, u(499));
+    assert!(!escrow.contract.is_solvent(TOKEN).unwrap());
+}
+
+// ============================================================================
+// Disputes
+// ============================================================================
+
+#[test]
+fn arbiter_resolves_full_dispute() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.eth_deal(&[600, 400]);
+
+    escrow.sender(FREELANCER);
+    escrow.contract.raise_dispute(deal_id).unwrap();
+    assert_eq!(escrow.status(deal_id), DISPUTED);
+    assert_eq!(escrow.contract.get_disputed_amount(deal_id), u(1000));
+
+    escrow.sender(ARBITER);
+    escrow
+        .contract
+        .resolve_dispute(deal_id, u(300), u(700))
+        .unwrap();
+    assert_eq!(escrow.eth_balance(CLIENT), u(285));
+    assert_eq!(escrow.eth_balance(FREELANCER), u(665));
+    assert_eq!(escrow.eth_balance(ARBITER), u(50));
+
+    assert_eq!(escrow.status(deal_id), COMPLETED);
+    assert_eq!(escrow.contract.get_deal_amount(deal_id), U256::ZERO);
+    assert_eq!(
+        escrow.contract.get_total_escrowed(Address::ZERO),
+        U256::ZERO
+    );
+
+    let resolved = &escrow.events::<DisputeResolved>()[0];
+    assert_eq!(resolved.client_amount, u(285));
+    assert_eq!(resolved.freelancer_amount, u(665));
+    assert_eq!(resolved.arbiter_fee, u(50));
+}
+
+#[test]
+fn loser_pays_deal_charges_the_fee_to_the_losing_party() {
+    let mut escrow = Escrow::new();
+    let deal_id =
+        escrow.deal_with_fee_mode(ARBITER, Address::ZERO, &[1000], Vec::new(), LOSER_PAYS);
+    assert_eq!(escrow.contract.get_fee_mode(deal_id), LOSER_PAYS);
+
+    escrow.sender(FREELANCER);
+    escrow.contract.raise_dispute(deal_id).unwrap();
+
+    escrow.sender(ARBITER);
+    escrow
+        .contract
+        .resolve_dispute(deal_id, u(200), u(800))
+        .unwrap();
+    assert_eq!(escrow.eth_balance(CLIENT), u(150));
+    assert_eq!(escrow.eth_balance(FREELANCER), u(800));
+    assert_eq!(escrow.eth_balance(ARBITER), u(50));
+
+    let resolved = &escrow.events::<DisputeResolved>()[0];
+    assert_eq!(resolved.client_amount, u(150));
+    assert_eq!(resolved.freelancer_amount, u(800));
+    assert_eq!(resolved.fee_mode, LOSER_PAYS);
+}
+
+#[test]
+#[should_panic]
+fn create_deal_rejects_unknown_fee_mode() {
+    let mut escrow = Escrow::new();
+    escrow.deal_with_fee_mode(ARBITER, Address::ZERO, &[1000], Vec::new(), 3);
+}
+
+#[test]
+fn unallocated_remainder_is_refunded_to_client() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.eth_deal(&[1000]);
+
+    escrow.sender(CLIENT);
+    escrow.contract.raise_dispute(deal_id).unwrap();
+
+    escrow.sender(ARBITER);
+    escrow
+        .contract
+        .resolve_dispute(deal_id, u(0), u(500))
+        .unwrap();
+
+    let resolved = &escrow.events::<DisputeResolved>()[0];
+    assert_eq!(resolved.client_amount, u(475));
+    assert_eq!(resolved.freelancer_amount, u(475));
+    assert_eq!(resolved.arbiter_fee, u(50));
+    assert_eq!(
+        escrow.contract.get_total_escrowed(Address::ZERO),
+        U256::ZERO
+    );
+}
+
+#[test]
+#[should_panic]
+fn only_arbiter_resolves() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.eth_deal(&[1000]);
+    escrow.sender(CLIENT);
+    escrow.contract.raise_dispute(deal_id).unwrap();
+
+    escrow.sender(CLIENT);
+    let _ = escrow.contract.resolve_dispute(deal_id, u(1000), u(0));
+}
+
+#[test]
+#[should_panic]
+fn strangers_cannot_raise_disputes() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.eth_deal(&[1000]);
+    escrow.sender(STRANGER);
+    let _ = escrow.contract.raise_dispute(deal_id);
+}
+
+#[test]
+#[should_panic]
+fn ruling_cannot_exceed_disputed_amount() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.eth_deal(&[1000]);
+    escrow.sender(CLIENT);
+    escrow.contract.raise_dispute(deal_id).unwrap();
+
+    escrow.sender(ARBITER);
+    let _ = escrow.contract.resolve_dispute(deal_id, u(600), u(600));
+}
+
+#[test]
+fn resolution_is_reported_to_registry() {
+    let mut escrow = Escrow::new();
+    let registry = escrow.registry();
+    let deal_id = escrow.eth_deal(&[1000]);
+
+    escrow.sender(CLIENT);
+    escrow.contract.raise_dispute(deal_id).unwrap();
+
+    registry.expect_record(ARBITER, deal_id, u(1000));
+    escrow.sender(ARBITER);
+    escrow
+        .contract
+        .resolve_dispute(deal_id, u(1000), U256::ZERO)
+        .unwrap();
+    assert_eq!(escrow.status(deal_id), COMPLETED);
+}
+
+#[test]
+fn failed_registry_report_does_not_block_payout() {
+    let mut escrow = Escrow::new();
+    let registry = escrow.registry();
+    let deal_id = escrow.eth_deal(&[1000]);
+
+    escrow.sender(CLIENT);
+    escrow.contract.raise_dispute(deal_id).unwrap();
+
+    registry.reject_record(ARBITER, deal_id, u(1000));
+    escrow.sender(ARBITER);
+    escrow
+        .contract
+        .resolve_dispute(deal_id, u(1000), U256::ZERO)
+        .unwrap();
+    assert_eq!(escrow.status(deal_id), COMPLETED);
+
+    let failed = escrow.events::<ResolutionReportFailed>();
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].arbiter, ARBITER);
+}
+
+#[test]
+fn milestone_dispute_leaves_other_milestones_flowing() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.eth_deal(&[600, 400]);
+
+    escrow.sender(CLIENT);
+    escrow
+        .contract
+        .raise_milestone_dispute(deal_id, vec![u(1)])
+        .unwrap();
+    assert!(escrow.contract.is_milestone_disputed(deal_id, u(1)));
+    assert!(!escrow.contract.is_milestone_disputed(deal_id, u(0)));
+    assert_eq!(escrow.contract.get_disputed_amount(deal_id), u(400));
+
+    escrow.sender(CLIENT);
+    escrow.contract.release_milestone(deal_id, u(0)).unwrap();
+    assert_eq!(escrow.status(deal_id), DISPUTED);
+
+    escrow.sender(ARBITER);
+    escrow
+        .contract
+        .resolve_dispute(deal_id, u(400), U256::ZERO)
+        .unwrap();
+    assert_eq!(escrow.status(deal_id), COMPLETED);
+}
+
+#[test]
+fn resolved_milestone_dispute_returns_deal_to_active() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.eth_deal(&[600, 400]);
+
+    escrow.sender(FREELANCER);
+    escrow
+        .contract
+        .raise_milestone_dispute(deal_id, vec![u(0)])
+        .unwrap();
+
+    escrow.sender(ARBITER);
+    escrow
+        .contract
+        .resolve_dispute(deal_id, u(300), u(300))
+        .unwrap();
+
+    assert_eq!(escrow.status(deal_id), ACTIVE);
+    assert_eq!(escrow.contract.get_deal_amount(deal_id), u(400));
+    assert_eq!(escrow.contract.get_total_escrowed(Address::ZERO), u(400));
+}
+
+#[test]
+#[should_panic]
+fn disputed_milestone_is_frozen() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.eth_deal(&[600, 400]);
+
+    escrow.sender(CLIENT);
+    escrow
+        .contract
+        .raise_milestone_dispute(deal_id, vec![u(1)])
+        .unwrap();
+    let _ = escrow.contract.release_milestone(deal_id, u(1));
+}
+
+#[test]
+fn per_milestone_ruling_records_outcomes() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.eth_deal(&[500, 300, 200]);
+
+    escrow.sender(CLIENT);
+    escrow.contract.raise_dispute(deal_id).unwrap();
+
+    escrow.sender(ARBITER);
+    escrow
+        .contract
+        .resolve_dispute_by_milestone(
+            deal_id,
+            vec![u(0), u(1), u(2)],
+            vec![2, 1, 3],
+            vec![U256::ZERO, U256::ZERO, u(50)],
+        )
+        .unwrap();
+
+    assert_eq!(
+        escrow
+            .contract
+            .get_milestone_outcome(deal_id, u(0))
+            .unwrap(),
+        (2, U256::ZERO)
+    );
+    assert_eq!(
+        escrow
+            .contract
+            .get_milestone_outcome(deal_id, u(1))
+            .unwrap(),
+        (1, u(300))
+    );
+    assert_eq!(
+        escrow
+            .contract
+            .get_milestone_outcome(deal_id, u(2))
+            .unwrap(),
+        (3, u(50))
+    );
+    assert_eq!(escrow.status(deal_id), COMPLETED);
+}
+
+#[test]
+#[should_panic]
+fn per_milestone_ruling_must_cover_every_disputed_milestone() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.eth_deal(&[500, 300]);
+
+    escrow.sender(CLIENT);
+    escrow.contract.raise_dispute(deal_id).unwrap();
+
+    escrow.sender(ARBITER);
+    let _ = escrow.contract.resolve_dispute_by_milestone(
+        deal_id,
+        vec![u(0)],
+        vec![2],
+        vec![U256::ZERO],
+    );
+}
+
+#[test]
+fn withdrawn_dispute_resumes_the_deal() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.eth_deal(&[600, 400]);
+
+    escrow.sender(CLIENT);
+    escrow.contract.raise_dispute(deal_id).unwrap();
+    escrow.contract.withdraw_dispute(deal_id).unwrap();
+
+    assert_eq!(escrow.status(deal_id), FUNDED);
+    assert_eq!(escrow.contract.get_disputed_amount(deal_id), U256::ZERO);
+    assert!(!escrow.contract.is_milestone_disputed(deal_id, u(0)));
+    assert_eq!(escrow.events::<DisputeWithdrawn>()[0].initiator, CLIENT);
+
+    escrow.contract.release_milestone(deal_id, u(0)).unwrap();
+    assert_eq!(escrow.eth_balance(FREELANCER), u(597));
+    assert_eq!(escrow.status(deal_id), ACTIVE);
+}
+
+#[test]
+fn withdrawal_after_a_release_resumes_as_active() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.eth_deal(&[600, 400]);
+
+    escrow.sender(FREELANCER);
+    escrow
+        .contract
+        .raise_milestone_dispute(deal_id, vec![u(1)])
+        .unwrap();
+
+    escrow.sender(CLIENT);
+    escrow.contract.release_milestone(deal_id, u(0)).unwrap();
+    assert_eq!(escrow.eth_balance(FREELANCER), u(597));
+
+    escrow.sender(FREELANCER);
+    escrow.contract.withdraw_dispute(deal_id).unwrap();
+    assert_eq!(escrow.status(deal_id), ACTIVE);
+    assert!(!escrow.contract.is_milestone_disputed(deal_id, u(1)));
+}
+
+#[test]
+#[should_panic]
+fn only_the_initiator_withdraws_a_dispute() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.eth_deal(&[1000]);
+    escrow.sender(CLIENT);
+    escrow.contract.raise_dispute(deal_id).unwrap();
+
+    escrow.sender(FREELANCER);
+    let _ = escrow.contract.withdraw_dispute(deal_id);
+}
+
+// ============================================================================
+// Dispute bonds
+// ============================================================================
+
+#[test]
+fn dispute_bond_config_is_per_token() {
+    let mut escrow = Escrow::new();
+    escrow.dispute_bond(Address::ZERO, u(100), U256::ZERO);
+    escrow.dispute_bond(TOKEN, U256::ZERO, u(250));
+
+    assert_eq!(
+        escrow.contract.get_dispute_bond_config(Address::ZERO),
+        (u(100), U256::ZERO)
+    );
+    assert_eq!(
+        escrow.contract.get_dispute_bond_config(TOKEN),
+        (U256::ZERO, u(250))
+    );
+    assert_eq!(
+        escrow.contract.get_dispute_bond_config(STRANGER),
+        (U256::ZERO, U256::ZERO)
+    );
+}
+
+#[test]
+fn matched_bond_goes_to_the_winner() {
+    let mut escrow = Escrow::new();
+    escrow.dispute_bond(Address::ZERO, u(100), U256::ZERO);
+    let deal_id = escrow.eth_deal(&[1000]);
+
+    escrow.sender(CLIENT).value(u(100));
+    escrow.contract.raise_dispute(deal_id).unwrap();
+    assert_eq!(escrow.contract.get_dispute_bond(deal_id), (u(100), false));
+
+    escrow.sender(FREELANCER).value(u(100));
+    escrow.contract.match_dispute_bond(deal_id).unwrap();
+    assert_eq!(escrow.contract.get_dispute_bond(deal_id), (u(100), true));
+    assert_eq!(escrow.events::<DisputeBondPosted>().len(), 2);
+    assert_eq!(escrow.contract.get_total_escrowed(Address::ZERO), u(1200));
+
+    escrow.sender(ARBITER);
+    escrow
+        .contract
+        .resolve_dispute(deal_id, U256::ZERO, u(1000))
+        .unwrap();
+    assert_eq!(escrow.eth_balance(FREELANCER), u(1150));
+    assert_eq!(escrow.eth_balance(ARBITER), u(50));
+
+    let released = &escrow.events::<DisputeBondsReleased>()[0];
+    assert_eq!(
+        (
+            released.initiator_amount,
+            released.respondent_amount,
+            released.arbiter_amount
+        ),
+        (U256::ZERO, u(200), U256::ZERO)
+    );
+    assert_eq!(
+        escrow.contract.get_dispute_bond(deal_id),
+        (U256::ZERO, false)
+    );
+    assert_eq!(
+        escrow.contract.get_total_escrowed(Address::ZERO),
+        U256::ZERO
+    );
+}
+
+#[test]
+fn unallocated_share_counts_toward_the_client_winning() {
+    let mut escrow = Escrow::new();
+    escrow.dispute_bond(Address::ZERO, u(100), U256::ZERO);
+    let deal_id = escrow.eth_deal(&[1000]);
+
+    escrow.sender(CLIENT).value(u(100));
+    escrow.contract.raise_dispute(deal_id).unwrap();
+    escrow.sender(FREELANCER).value(u(100));
+    escrow.contract.match_dispute_bond(deal_id).unwrap();
+
+    // The unallocated 300 is refunded to the client, who receives 600 gross against 400
+    escrow.sender(ARBITER);
+    escrow
+        .contract
+        .resolve_dispute(deal_id, u(300), u(400))
+        .unwrap();
+    assert_eq!(escrow.eth_balance(CLIENT), u(570 + 200));
+    assert_eq!(escrow.eth_balance(FREELANCER), u(380));
+
+    let released = &escrow.events::<DisputeBondsReleased>()[0];
+    assert_eq!(
+        (released.initiator_amount, released.respondent_amount),
+        (u(200), U256::ZERO)
+    );
+}
+
+#[test]
+fn unmatched_bond_of_a_losing_initiator_goes_to_the_arbiter() {
+    let mut escrow = Escrow::new();
+    escrow.dispute_bond(Address::ZERO, U256::ZERO, u(1000));
+    let deal_id = escrow.eth_deal(&[600, 400]);
+
+    // 10% of the 1000 remaining
+    escrow.sender(CLIENT).value(u(100));
+    escrow.contract.raise_dispute(deal_id).unwrap();
+
+    escrow.sender(ARBITER);
+    escrow
+        .contract
+        .resolve_dispute(deal_id, U256::ZERO, u(1000))
+        .unwrap();
+    assert_eq!(escrow.eth_balance(FREELANCER), u(950));
+    assert_eq!(escrow.eth_balance(ARBITER), u(150));
+
+    let released = &escrow.events::<DisputeBondsReleased>()[0];
+    assert_eq!(released.arbiter_amount, u(100));
+    assert_eq!(
+        escrow.contract.get_total_escrowed(Address::ZERO),
+        U256::ZERO
+    );
+}
+
+#[test]
+fn split_ruling_refunds_both_bonds() {
+    let mut escrow = Escrow::new();
+    escrow.dispute_bond(TOKEN, u(100), U256::ZERO);
+    let deal_id = escrow.deal_with(ARBITER, TOKEN, &[1000], Vec::new());
+    let erc20 = escrow.erc20();
+
+    erc20.expect_transfer_from(FREELANCER, ESCROW, u(100));
+    escrow.sender(FREELANCER);
+    escrow.contract.raise_dispute(deal_id).unwrap();
+    erc20.expect_transfer_from(CLIENT, ESCROW, u(100));
+    escrow.sender(CLIENT);
+    escrow.contract.match_dispute_bond(deal_id).unwrap();
+
+    erc20.expect_transfer(CLIENT, u(475));
+    erc20.expect_transfer(FREELANCER, u(475));
+    erc20.expect_transfer(ARBITER, u(50));
+    erc20.expect_transfer(CLIENT, u(100));
+    erc20.expect_transfer(FREELANCER, u(100));
+    escrow.sender(ARBITER);
+    escrow
+        .contract
+        .resolve_dispute(deal_id, u(500), u(500))
+        .unwrap();
+
+    let released = &escrow.events::<DisputeBondsReleased>()[0];
+    assert_eq!(
+        (released.initiator_amount, released.respondent_amount),
+        (u(100), u(100))
+    );
+    assert_eq!(escrow.contract.get_total_escrowed(TOKEN), U256::ZERO);
+}
+
+#[test]
+fn withdrawal_refunds_the_bond() {
+    let mut escrow = Escrow::new();
+    escrow.dispute_bond(Address::ZERO, u(100), U256::ZERO);
+    let deal_id = escrow.eth_deal(&[1000]);
+
+    escrow.sender(CLIENT).value(u(100));
+    escrow.contract.raise_dispute(deal_id).unwrap();
+
+    escrow.sender(CLIENT);
+    escrow.contract.withdraw_dispute(deal_id).unwrap();
+    assert_eq!(escrow.eth_balance(CLIENT), u(100));
+    assert_eq!(escrow.contract.get_total_escrowed(Address::ZERO), u(1000));
+}
+
+#[test]
+#[should_panic]
+fn dispute_requires_the_bond() {
+    let mut escrow = Escrow::new();
+    escrow.dispute_bond(Address::ZERO, u(100), U256::ZERO);
+    let deal_id = escrow.eth_deal(&[1000]);
+
+    escrow.sender(CLIENT);
+    let _ = escrow.contract.raise_dispute(deal_id);
+}
+
+#[test]
+#[should_panic]
+fn initiator_cannot_match_their_own_bond() {
+    let mut escrow = Escrow::new();
+    escrow.dispute_bond(Address::ZERO, u(100), U256::ZERO);
+    let deal_id = escrow.eth_deal(&[1000]);
+
+    escrow.sender(CLIENT).value(u(100));
+    escrow.contract.raise_dispute(deal_id).unwrap();
+    let _ = escrow.contract.match_dispute_bond(deal_id);
+}
+
+// ============================================================================
+// Mutual settlement
+// ============================================================================
+
+#[test]
+fn parties_settle_without_the_arbiter() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.eth_deal(&[600, 400]);
+
+    escrow.sender(CLIENT);
+    escrow
+        .contract
+        .propose_settlement(deal_id, u(300), u(700))
+        .unwrap();
+    assert_eq!(
+        escrow.contract.get_settlement(deal_id),
+        (CLIENT, u(300), u(700))
+    );
+
+    // No arbiter fee; the freelancer's share carries the 0.5% release fee
+    escrow.sender(FREELANCER);
+    escrow
+        .contract
+        .accept_settlement(deal_id, u(300), u(700))
+        .unwrap();
+    assert_eq!(escrow.eth_balance(CLIENT), u(300));
+    assert_eq!(escrow.eth_balance(FREELANCER), u(697));
+
+    assert_eq!(escrow.status(deal_id), COMPLETED);
+    assert_eq!(escrow.contract.get_deal_amount(deal_id), U256::ZERO);
+    assert_eq!(
+        escrow.contract.get_settlement(deal_id),
+        (Address::ZERO, U256::ZERO, U256::ZERO)
+    );
+    assert_eq!(
+        escrow.contract.get_total_escrowed(Address::ZERO),
+        U256::ZERO
+    );
+    assert_eq!(escrow.contract.get_accrued_fees(Address::ZERO), u(3));
+
+    let accepted = &escrow.events::<SettlementAccepted>()[0];
+    assert_eq!(accepted.client_amount, u(300));
+    assert_eq!(accepted.freelancer_amount, u(697));
+    assert_eq!(accepted.fee, u(3));
+}
+
+#[test]
+fn settlement_closes_an_open_dispute() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.eth_deal(&[1000]);
+
+    escrow.sender(CLIENT);
+    escrow.contract.raise_dispute(deal_id).unwrap();
+
+    escrow.sender(FREELANCER);
+    escrow
+        .contract
+        .propose_settlement(deal_id, u(500), u(500))
+        .unwrap();
+
+    escrow.sender(CLIENT);
+    escrow
+        .contract
+        .accept_settlement(deal_id, u(500), u(500))
+        .unwrap();
+    assert_eq!(escrow.eth_balance(CLIENT), u(500));
+    assert_eq!(escrow.eth_balance(FREELANCER), u(498));
+
+    assert_eq!(escrow.status(deal_id), COMPLETED);
+    assert_eq!(escrow.contract.get_disputed_amount(deal_id), U256::ZERO);
+    assert!(escrow.events::<DisputeResolved>().is_empty());
+}
+
+#[test]
+#[should_panic]
+fn proposer_cannot_accept_own_offer() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.eth_deal(&[1000]);
+
+    escrow.sender(CLIENT);
+    escrow
+        .contract
+        .propose_settlement(deal_id, u(1000), U256::ZERO)
+        .unwrap();
+    let _ = escrow
+        .contract
+        .accept_settlement(deal_id, u(1000), U256::ZERO);
+}
+
+#[test]
+#[should_panic]
+fn acceptance_must_match_the_offer() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.eth_deal(&[1000]);
+
+    escrow.sender(CLIENT);
+    escrow
+        .contract
+        .propose_settlement(deal_id, u(400), u(600))
+        .unwrap();
+
+    escrow.sender(FREELANCER);
+    let _ = escrow.contract.accept_settlement(deal_id, u(300), u(700));
+}
+
+#[test]
+#[should_panic]
+fn offer_must_cover_the_remaining_amount() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.eth_deal(&[1000]);
+
+    escrow.sender(CLIENT);
+    let _ = escrow.contract.propose_settlement(deal_id, u(100), u(100));
+}
+
+#[test]
+#[should_panic]
+fn release_invalidates_open_offer() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.eth_deal(&[600, 400]);
+
+    escrow.sender(FREELANCER);
+    escrow
+        .contract
+        .propose_settlement(deal_id, U256::ZERO, u(1000))
+        .unwrap();
+
+    escrow.sender(CLIENT);
+    escrow.contract.release_milestone(deal_id, u(0)).unwrap();
+    let _ = escrow
+        .contract
+        .accept_settlement(deal_id, U256::ZERO, u(1000));
+}
+
+#[test]
+#[should_panic]
+fn outsiders_cannot_propose_settlements() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.eth_deal(&[1000]);
+
+    escrow.sender(ARBITER);
+    let _ = escrow.contract.propose_settlement(deal_id, u(500), u(500));
+}
+
+// ============================================================================
+// Arbiter panels
+// ============================================================================
+
+#[test]
+fn panel_settles_on_majority() {
+    let mut escrow = Escrow::new();
+    let panel = vec![ARBITER, ARBITER_2, ARBITER_3];
+    let deal_id = escrow.deal_with(Address::ZERO, Address::ZERO, &[1000], panel.clone());
+    assert_eq!(escrow.contract.get_deal_panel(deal_id), panel);
+    assert_eq!(escrow.contract.get_deal_arbiter(deal_id), ARBITER);
+
+    escrow.sender(CLIENT);
+    escrow.contract.raise_dispute(deal_id).unwrap();
+
+    escrow.sender(ARBITER);
+    escrow
+        .contract
+        .resolve_dispute(deal_id, u(800), u(200))
+        .unwrap();
+    assert_eq!(escrow.status(deal_id), DISPUTED);
+    assert_eq!(
+        escrow.contract.get_panel_vote(deal_id, u(0)).unwrap(),
+        (ARBITER, u(800), u(200))
+    );
+
+    escrow.sender(ARBITER_3);
+    escrow
+        .contract
+        .resolve_dispute(deal_id, u(800), u(200))
+        .unwrap();
+    assert_eq!(escrow.status(deal_id), COMPLETED);
+    assert_eq!(escrow.events::<DisputeResolved>()[0].arbiter_fee, u(50));
+}
+
+#[test]
+fn panel_falls_back_to_median_ruling() {
+    let mut escrow = Escrow::new();
+    let panel = vec![ARBITER, ARBITER_2, ARBITER_3];
+    let deal_id = escrow.deal_with(Address::ZERO, Address::ZERO, &[1000], panel);
+
+    escrow.sender(CLIENT);
+    escrow.contract.raise_dispute(deal_id).unwrap();
+
+    for (arbiter, client_share) in [(ARBITER, 900), (ARBITER_2, 100), (ARBITER_3, 500)] {
+        escrow.sender(arbiter);
+        escrow
+            .contract
+            .resolve_dispute(deal_id, u(client_share), u(1000 - client_share))
+            .unwrap();
+    }
+
+    let resolved = &escrow.events::<DisputeResolved>()[0];
+    assert_eq!(resolved.client_amount, u(475));
+    assert_eq!(resolved.freelancer_amount, u(475));
+}
+
+#[test]
+#[should_panic]
+fn panelist_cannot_vote_twice() {
+    let mut escrow = Escrow::new();
+    let panel = vec![ARBITER, ARBITER_2, ARBITER_3];
+    let deal_id = escrow.deal_with(Address::ZERO, Address::ZERO, &[1000], panel);
+
+    escrow.sender(CLIENT);
+    escrow.contract.raise_dispute(deal_id).unwrap();
+
+    escrow.sender(ARBITER);
+    escrow
+        .contract
+        .resolve_dispute(deal_id, u(800), u(200))
+        .unwrap();
+    let _ = escrow.contract.resolve_dispute(deal_id, u(800), u(200));
+}
+
+#[test]
+#[should_panic]
+fn panel_must_have_three_or_five_members() {
+    let mut escrow = Escrow::new();
+    escrow.deal_with(
+        Address::ZERO,
+        Address::ZERO,
+        &[1000],
+        vec![ARBITER, ARBITER_2, ARBITER_3, ARBITER_4],
+    );
+}
+
+// ============================================================================
+// Appeals
+// ============================================================================
+
+fn appealable_dispute(escrow: &mut Escrow) -> U256 {
+    escrow.sender(ADMIN);
+    let operation_id = escrow
+        .contract
+        .schedule_appeal_config(u(3600), u(1000), COURT)
+        .unwrap();
+    escrow.execute_after_delay(operation_id);
+
+    let deal_id = escrow.eth_deal(&[1000]);
+    escrow.sender(CLIENT);
+    escrow.contract.raise_dispute(deal_id).unwrap();
+
+    escrow.sender(ARBITER).at(2_000);
+    escrow
+        .contract
+        .resolve_dispute(deal_id, u(200), u(800))
+        .unwrap();
+    deal_id
+}
+
+#[test]
+fn ruling_executes_after_appeal_window() {
+    let mut escrow = Escrow::new();
+    let deal_id = appealable_dispute(&mut escrow);
+
+    assert_eq!(escrow.status(deal_id), DISPUTED);
+    assert_eq!(
+        escrow.contract.get_pending_ruling(deal_id),
+        (u(200), u(800), u(5_600), Address::ZERO)
+    );
+    assert_eq!(escrow.events::<RulingIssued>().len(), 1);
+
+    escrow.sender(STRANGER).at(5_600);
+    escrow.contract.execute_ruling(deal_id).unwrap();
+    assert_eq!(escrow.status(deal_id), COMPLETED);
+}
+
+#[test]
+#[should_panic]
+fn ruling_cannot_execute_inside_window() {
+    let mut escrow = Escrow::new();
+    let deal_id = appealable_dispute(&mut escrow);
+
+    escrow.sender(STRANGER).at(5_599);
+    let _ = escrow.contract.execute_ruling(deal_id);
+}
+
+#[test]
+fn successful_appeal_refunds_bond() {
+    let mut escrow = Escrow::new();
+    let deal_id = appealable_dispute(&mut escrow);
+
+    escrow.sender(CLIENT).value(u(100)).at(3_000);
+    escrow.contract.appeal(deal_id).unwrap();
+    assert_eq!(escrow.contract.get_pending_ruling(deal_id).3, CLIENT);
+    assert_eq!(escrow.contract.get_total_escrowed(Address::ZERO), u(1100));
+
+    escrow.sender(COURT);
+    escrow
+        .contract
+        .resolve_appeal(deal_id, u(700), u(300))
+        .unwrap();
+    // The client's share net of the arbiter fee, plus the refunded bond
+    assert_eq!(escrow.eth_balance(CLIENT), u(665 + 100));
+    assert_eq!(escrow.status(deal_id), COMPLETED);
+    assert_eq!(
+        escrow.contract.get_total_escrowed(Address::ZERO),
+        U256::ZERO
+    );
+}
+
+#[test]
+#[should_panic]
+fn appeal_closes_with_window() {
+    let mut escrow = Escrow::new();
+    let deal_id = appealable_dispute(&mut escrow);
+
+    escrow.sender(CLIENT).value(u(100)).at(5_600);
+    let _ = escrow.contract.appeal(deal_id);
+}
+
+#[test]
+#[should_panic]
+fn only_court_resolves_appeals() {
+    let mut escrow = Escrow::new();
+    let deal_id = appealable_dispute(&mut escrow);
+
+    escrow.sender(CLIENT).value(u(100)).at(3_000);
+    escrow.contract.appeal(deal_id).unwrap();
+
+    escrow.sender(ARBITER);
+    let _ = escrow.contract.resolve_appeal(deal_id, u(700), u(300));
+}
+
+#[test]
+fn appeal_court_and_arbiter_split_fee() {
+    let mut escrow = Escrow::new();
+    let registry = escrow.registry();
+    let deal_id = appealable_dispute(&mut escrow);
+
+    escrow.sender(CLIENT).value(u(100)).at(3_000);
+    escrow.contract.appeal(deal_id).unwrap();
+    assert_eq!(
+        escrow.contract.get_pending_ruling(deal_id).2,
+        u(3_000 + APPEAL_RULING_WINDOW)
+    );
+
+    // The court overturns the ruling, so the arbiter is paid but not credited
+    registry.reject_record(ARBITER, deal_id, u(1000));
+    escrow.sender(COURT);
+    escrow
+        .contract
+        .resolve_appeal(deal_id, u(700), u(300))
+        .unwrap();
+    assert_eq!(escrow.eth_balance(COURT), u(25));
+    assert_eq!(escrow.eth_balance(ARBITER), u(25));
+    assert!(escrow.events::<ResolutionReportFailed>().is_empty());
+}
+
+#[test]
+fn lapsed_appeal_executes_original_ruling() {
+    let mut escrow = Escrow::new();
+    let deal_id = appealable_dispute(&mut escrow);
+
+    escrow.sender(CLIENT).value(u(100)).at(3_000);
+    escrow.contract.appeal(deal_id).unwrap();
+
+    escrow.sender(STRANGER).at(3_000 + APPEAL_RULING_WINDOW);
+    escrow.contract.execute_ruling(deal_id).unwrap();
+    assert_eq!(escrow.status(deal_id), COMPLETED);
+    // The original client share net of the arbiter fee, plus the refunded bond
+    assert_eq!(escrow.eth_balance(CLIENT), u(190 + 100));
+    assert_eq!(escrow.eth_balance(ARBITER), u(50));
+    assert_eq!(escrow.eth_balance(COURT), U256::ZERO);
+}
+
+#[test]
+#[should_panic]
+fn appeal_court_rules_before_deadline() {
+    let mut escrow = Escrow::new();
+    let deal_id = appealable_dispute(&mut escrow);
+
+    escrow.sender(CLIENT).value(u(100)).at(3_000);
+    escrow.contract.appeal(deal_id).unwrap();
+
+    escrow.sender(COURT).at(3_000 + APPEAL_RULING_WINDOW);
+    let _ = escrow.contract.resolve_appeal(deal_id, u(700), u(300));
+}
+
+// ============================================================================
+// Arbiter reassignment and selection
+// ============================================================================
+
+#[test]
+fn parties_agree_on_new_arbiter() {
+    let mut escrow = Escrow::new();
+    let deal_id = escrow.eth_deal(&[1000]);
+
+    escrow.sender(CLIENT);
+    escrow.contract.propose_arbiter(deal_id, ARBITER, ARBITER_2);
+    assert_eq!(
+        escrow.contract.get_arbiter_proposal(deal_id),
+        (ARBITER, ARBITER_2, CLIENT)
+    );
+    assert_eq!(escrow.contract.get_deal_arbiter(deal_id), ARBITER);
+
+    escrow.sender(FREELANCER);
+    escrow.contract.propose_arbiter(deal_id, ARBITER, ARBITER_2);
+    assert_eq!(escrow.contract.get_deal_arbiter(deal_id), ARBITER_2);
+    assert_eq!(
+        escrow.events::<ArbiterReassigned>()[0].new_arbiter,
+        ARBITER_2
+    );
+}
+
+#[test]
+fn recused_arbiter_is_redrawn_from_registry() {
+    let mut escrow = Escrow::new();
+    let registry = escrow.registry();
+    let deal_id = escrow.eth_deal(&[1000]);
+
+    escrow.sender(ARBITER);
+    escrow.contract.recuse(deal_id);
+    assert_eq!(escrow.contract.get_deal_arbiter(deal_id), Address::ZERO);
+
+    let (client_secret, freelancer_secret) = (u(1111), u(2222));
+    escrow.sender(CLIENT);
+    escrow
+        .contract
+        .commit_arbiter_seed(deal_id, commitment(client_secret, CLIENT));
+    escrow.sender(FREELANCER);
+    escrow
+        .contract
+        .commit_arbiter_seed(deal_id, commitment(freelancer_secret, FREELANCER));
+
+    let seed = selection_seed(client_secret, freelancer_secret, deal_id);
+    registry.select(seed, vec![CLIENT, FREELANCER], ARBITER_2);
+
+    escrow.sender(CLIENT);
+    escrow.contract.reveal_arbiter_seed(deal_id, client_secret);
+    escrow.sender(FREELANCER);
+    escrow
+        .contract
+        .reveal_arbiter_seed(deal_id, freelancer_secret);
+    assert_eq!(escrow.contract.get_deal_arbiter(deal_id), ARBITER_2);
+}
+
+#[test]
+fn inactive_arbiter_is_redrawn_from_registry() {
+    let mut escrow = Escrow::new();
+    let registry = escrow.registry();
+    let deal_id = escrow.eth_deal(&[1000]);
+
+    registry.set_status(ARBITER, false);
+    escrow.sender(FREELANCER);
+    escrow.contract.replace_inactive_arbiter(deal_id, ARBITER);
+    assert_eq!(escrow.contract.get_deal_arbiter(deal_id), Address::ZERO);
+
+    let (client_secret, freelancer_secret) = (u(1111), u(2222));
+    escrow.sender(CLIENT);
+    escrow
+        .contract
+        .commit_arbiter_seed(deal_id, commitment(client_secret, CLIENT));
+    escrow.sender(FREELANCER);
+    escrow
+        .contract
+        .commit_arbiter_seed(deal_id, commitment(freelancer_secret, FREELANCER));
+
+    let seed = selection_seed(client_secret, freelancer_secret, deal_id);
+    registry.select(seed, vec![CLIENT, FREELANCER], ARBITER_2);
+
+    escrow.sender(CLIENT);
+    escrow.contract.reveal_arbiter_seed(deal_id, client_secret);
+    escrow.sender(FREELANCER);
+    escrow
+        .contract
+        .reveal_arbiter_seed(deal_id, freelancer_secret);
+    assert_eq!(escrow.contract.get_deal_arbiter(deal_id), ARBITER_2);
+}
+
+#[test]
+#[should_panic]
+fn vacant_seat_cannot_be_replaced_by_a_party() {
+    let mut escrow = Escrow::new();
+    escrow.registry();
+    let deal_id = escrow.eth_deal(&[1000]);
+
+    escrow.sender(ARBITER);
+    escrow.contract.recuse(deal_id);
+
+    escrow.sender(FREELANCER);
+    escrow
+        .contract
+        .replace_inactive_arbiter(deal_id, Address::ZERO);
+}
+
+#[test]
+fn recused_panelist_is_redrawn_from_registry() {
+    let mut escrow = Escrow::new();
+    let registry = escrow.registry();
+    let panel = vec![ARBITER, ARBITER_2, ARBITER_3];
+    let deal_id = escrow.deal_with(Address::ZERO, Address::ZERO, &[1000], panel);
+
+    escrow.sender(ARBITER_2);
+    escrow.contract.recuse(deal_id);
+
+    let (client_secret, freelancer_secret) = (u(1111), u(2222));
+    escrow.sender(CLIENT);
+    escrow
+        .contract
+        .commit_arbiter_seed(deal_id, commitment(client_secret, CLIENT));
+    escrow.sender(FREELANCER);
+    escrow
+        .contract
+        .commit_arbiter_seed(deal_id, commitment(freelancer_secret, FREELANCER));
+
+    // Sitting panelists cannot be drawn a second time
+    let seed = selection_seed(client_secret, freelancer_secret, deal_id);
+    registry.select(
+        seed,
+        vec![CLIENT, FREELANCER, ARBITER, ARBITER_3],
+        ARBITER_4,
+    );
+
+    escrow.sender(CLIENT);
+    escrow.contract.reveal_arbiter_seed(deal_id, client_secret);
+    escrow.sender(FREELANCER);
+    escrow
+        .contract
+        .reveal_arbiter_seed(deal_id, freelancer_secret);
+    assert_eq!(
+        escrow.contract.get_deal_panel(deal_id),
+        vec![ARBITER, ARBITER_4, ARBITER_3]
+    );
+}
+
+#[test]
+#[should_panic]
+fn active_arbiter_cannot_be_replaced_from_registry() {
+    let mut escrow = Escrow::new();
+    let registry = escrow.registry();
+    let deal_id = escrow.eth_deal(&[1000]);
+
+    registry.set_status(ARBITER, true);
+    escrow.sender(CLIENT);
+    escrow.contract.replace_inactive_arbiter(deal_id, ARBITER);
+}
+
+#[test]
+fn arbiter_is_drawn_after_commit_reveal() {
+    let mut escrow = Escrow::new();
+    let registry = escrow.registry();
+    let deal_id = escrow.deal_with(Address::ZERO, Address::ZERO, &[1000], Vec::new());
+    assert_eq!(escrow.contract.get_deal_arbiter(deal_id), Address::ZERO);
+
+    let (client_secret, freelancer_secret) = (u(1111), u(2222));
+    assert_eq!(
+        arbiter_seed_commitment(client_secret, CLIENT),
+        commitment(client_secret, CLIENT)
+    );
+
+    escrow.sender(CLIENT);
+    escrow
+        .contract
+        .commit_arbiter_seed(deal_id, commitment(client_secret, CLIENT));
+    escrow.sender(FREELANCER);
+    escrow
+        .contract
+        .commit_arbiter_seed(deal_id, commitment(freelancer_secret, FREELANCER));
+
+    let seed = selection_seed(client_secret, freelancer_secret, deal_id);
+    registry.select(seed, vec![CLIENT, FREELANCER], ARBITER_3);
+
+    escrow.sender(CLIENT);
+    escrow.contract.reveal_arbiter_seed(deal_id, client_secret);
+    escrow.sender(FREELANCER);
+    escrow
+        .contract
+        .reveal_arbiter_seed(deal_id, freelancer_secret);
+
+    assert_eq!(escrow.contract.get_deal_arbiter(deal_id), ARBITER_3);
+}
+
+/// Opens a commit-reveal round at `t = 1_000` on a deal without an arbiter.
+fn committed_draw(
+    escrow: &mut Escrow,
+    client_secret: U256,
+    freelancer_secret: U256,
+) -> (U256, MockRegistry) {
+    let registry = escrow.registry();
+    let deal_id = escrow.deal_with(Address::ZERO, Address::ZERO, &[1000], Vec::new());
+
+    escrow.sender(CLIENT).at(1_000);
+    escrow
+        .contract
+        .commit_arbiter_seed(deal_id, commitment(client_secret, CLIENT));
+    escrow.sender(FREELANCER);
+    escrow
+        .contract
+        .commit_arbiter_seed(deal_id, commitment(freelancer_secret, FREELANCER));
+    assert_eq!(
+        escrow.contract.get_seed_deadline(deal_id),
+        1_000 + SEED_REVEAL_WINDOW
+    );
+    (deal_id, registry)
+}
+
+#[test]
+fn withheld_reveal_falls_back_to_block_data() {
+    let mut escrow = Escrow::new();
+    let client_secret = u(1111);
+    let (deal_id, registry) = committed_draw(&mut escrow, client_secret, u(2222));
+
+    escrow.sender(CLIENT);
+    escrow.contract.reveal_arbiter_seed(deal_id, client_secret);
+
+    let timestamp = 1_001 + SEED_REVEAL_WINDOW;
+    escrow.vm.set_block_number(77);
+    let mut data = client_secret.to_be_bytes::<32>().to_vec();
+    data.extend_from_slice(&77u64.to_be_bytes());
+    data.extend_from_slice(&timestamp.to_be_bytes());
+    let mixed = U256::from_be_bytes(alloy_primitives::keccak256(&data).0);
+    let seed = selection_seed(mixed, U256::ZERO, deal_id);
+    registry.select(seed, vec![CLIENT, FREELANCER], ARBITER_2);
+
+    escrow.sender(CLIENT).at(timestamp);
+    escrow.contract.conclude_arbiter_draw(deal_id);
+    assert_eq!(escrow.contract.get_deal_arbiter(deal_id), ARBITER_2);
+    assert_eq!(escrow.contract.get_seed_deadline(deal_id), 0);
+}
+
+#[test]
+#[should_panic]
+fn reveal_closes_with_deadline() {
+    let mut escrow = Escrow::new();
+    let (deal_id, _) = committed_draw(&mut escrow, u(1111), u(2222));
+
+    escrow.sender(CLIENT).at(1_001 + SEED_REVEAL_WINDOW);
+    escrow.contract.reveal_arbiter_seed(deal_id, u(1111));
+}
+
+#[test]
+#[should_panic]
+fn draw_cannot_be_concluded_before_deadline() {
+    let mut escrow = Escrow::new();
+    let (deal_id, _) = committed_draw(&mut escrow, u(1111), u(2222));
+
+    escrow.sender(CLIENT).at(1_000 + SEED_REVEAL_WINDOW);
+    escrow.contract.conclude_arbiter_draw(deal_id);
+}
+
+#[test]
+fn unrevealed_round_is_reset() {
+    let mut escrow = Escrow::new();
+    let (deal_id, _) = committed_draw(&mut escrow, u(1111), u(2222));
+
+    escrow.sender(FREELANCER).at(1_001 + SEED_REVEAL_WINDOW);
+    escrow.contract.conclude_arbiter_draw(deal_id);
+    assert_eq!(escrow.contract.get_seed_deadline(deal_id), 0);
+
+    // Both parties can commit afresh
+    escrow.sender(CLIENT);
+    escrow
+        .contract
+        .commit_arbiter_seed(deal_id, commitment(u(3333), CLIENT));
+    assert_eq!(
+        escrow.contract.get_seed_deadline(deal_id),
+        1_001 + 2 * SEED_REVEAL_WINDOW
+    );
+}
+
+#[test]
+#[should_panic]
+fn reveal_must_match_commitment() {
+    let mut escrow = Escrow::new();
+    escrow.registry();
+    let deal_id = escrow.deal_with(Address::ZERO, Address::ZERO, &[1000], Vec::new());
+
+    escrow.sender(CLIENT);
+    escrow
+        .contract
+        .commit_arbiter_seed(deal_id, commitment(u(1), CLIENT));
+    escrow.sender(FREELANCER);
+    escrow
+        .contract
+        .commit_arbiter_seed(deal_id, commitment(u(2), FREELANCER));
+
+    escrow.sender(CLIENT);
+    escrow.contract.reveal_arbiter_seed(deal_id, u(3));
+}
@@ -0,0 +1,367 @@
+//! Randomized action-sequence testing of the deal state machine.
+//!
+//! Random `create_deal`, `release_milestone`, `raise_dispute` and `resolve_dispute` calls are
+//! issued by random actors against single-arbiter ETH deals. Calls that are expected to fail
+//! are issued as well: a reverted call must leave no trace, and after every step the
+//! accounting invariants below must hold.
+
+mod common;
+
+use std::collections::HashSet;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use alloy_primitives::{Address, U256};
+use arbisecure_contracts::{DisputeResolved, MilestoneReleased, BPS_DENOMINATOR};
+use common::*;
+use proptest::prelude::*;
+
+const ACTORS: [Address; 4] = [CLIENT, FREELANCER, ARBITER, STRANGER];
+
+/// Seconds a time-locked milestone stays locked after deal creation
+const TIME_LOCK: u64 = 1_000;
+
+#[derive(Clone, Debug)]
+enum Action {
+    /// Milestones are (amount, requires_approval, time_locked)
+    Create {
+        creator: usize,
+        milestones: Vec<(u64, bool, bool)>,
+    },
+    Release {
+        actor: usize,
+        deal: usize,
+        milestone: usize,
+    },
+    RaiseDispute {
+        actor: usize,
+        deal: usize,
+    },
+    /// Shares are basis points of the disputed amount and may over-allocate it
+    Resolve {
+        actor: usize,
+        deal: usize,
+        client_bps: u64,
+        freelancer_bps: u64,
+    },
+    Warp {
+        seconds: u64,
+    },
+}
+
+fn action() -> impl Strategy<Value = Action> {
+    let milestone = (1..1_000_000_000u64, any::<bool>(), any::<bool>());
+    prop_oneof![
+        (0..ACTORS.len(), prop::collection::vec(milestone, 1..5)).prop_map(
+            |(creator, milestones)| Action::Create {
+                creator,
+                milestones
+            }
+        ),
+        (0..ACTORS.len(), any::<usize>(), 0..6usize).prop_map(|(actor, deal, milestone)| {
+            Action::Release {
+                actor,
+                deal,
+                milestone,
+            }
+        }),
+        (0..ACTORS.len(), any::<usize>())
+            .prop_map(|(actor, deal)| Action::RaiseDispute { actor, deal }),
+        (
+            0..ACTORS.len(),
+            any::<usize>(),
+            0..=12_000u64,
+            0..=12_000u64
+        )
+            .prop_map(
+                |(actor, deal, client_bps, freelancer_bps)| Action::Resolve {
+                    actor,
+                    deal,
+                    client_bps,
+                    freelancer_bps,
+                }
+            ),
+        (1..2 * TIME_LOCK).prop_map(|seconds| Action::Warp { seconds }),
+    ]
+}
+
+/// Observable state of one deal
+#[derive(Clone, Debug, PartialEq)]
+struct DealView {
+    status: u64,
+    remaining: U256,
+    disputed: U256,
+    /// (amount, is_released, is_disputed) per milestone
+    milestones: Vec<(U256, bool, bool)>,
+}
+
+/// Observable state of the whole contract
+#[derive(Clone, Debug, PartialEq)]
+struct Snapshot {
+    deals: Vec<DealView>,
+    escrowed: U256,
+    fees: U256,
+    logs: usize,
+}
+
+struct Fuzzer {
+    escrow: Escrow,
+    deals: usize,
+    deposited: U256,
+    now: u64,
+}
+
+impl Fuzzer {
+    fn new() -> Self {
+        Self {
+            escrow: Escrow::new(),
+            deals: 0,
+            deposited: U256::ZERO,
+            now: 1_000,
+        }
+    }
+
+    /// Maps a random index onto a known deal, or one past the last deal (which does not exist).
+    fn deal_id(&self, deal: usize) -> U256 {
+        u((deal % (self.deals + 1)) as u64)
+    }
+
+    fn run(&mut self, action: &Action) {
+        let before = self.snapshot();
+
+        let (succeeded, target) = match action {
+            Action::Create {
+                creator,
+                milestones,
+            } => {
+                let total: u64 = milestones.iter().map(|m| m.0).sum();
+                let end_time = |locked: bool| {
+                    if locked {
+                        u(self.now + TIME_LOCK)
+                    } else {
+                        U256::ZERO
+                    }
+                };
+                let end_times = milestones.iter().map(|m| end_time(m.2)).collect();
+                let approvals = milestones.iter().map(|m| u(m.1 as u64)).collect();
+                let amounts = milestones.iter().map(|m| u(m.0)).collect();
+
+                self.escrow.sender(ACTORS[*creator]).value(u(total));
+                let contract = &mut self.escrow.contract;
+                let succeeded = attempt(|| {
+                    contract.create_deal(
+                        U256::ZERO,
+                        FREELANCER,
+                        ARBITER,
+                        Address::ZERO,
+                        u(total),
+                        amounts,
+                        end_times,
+                        approvals,
+                    )
+                });
+                if succeeded {
+                    self.deals += 1;
+                    self.deposited += u(total);
+                }
+                (succeeded, None)
+            }
+            Action::Release {
+                actor,
+                deal,
+                milestone,
+            } => {
+                let deal_id = self.deal_id(*deal);
+                let index = u(*milestone as u64);
+                self.escrow.sender(ACTORS[*actor]);
+                let contract = &mut self.escrow.contract;
+                let succeeded = attempt(|| contract.release_milestone(deal_id, index));
+                (succeeded, Some(deal_id))
+            }
+            Action::RaiseDispute { actor, deal } => {
+                let deal_id = self.deal_id(*deal);
+
+                self.escrow.sender(ACTORS[*actor]);
+                let contract = &mut self.escrow.contract;
+                let succeeded = attempt(|| contract.raise_dispute(deal_id));
+                (succeeded, Some(deal_id))
+            }
+            Action::Resolve {
+                actor,
+                deal,
+                client_bps,
+                freelancer_bps,
+            } => {
+                let deal_id = self.deal_id(*deal);
+                let disputed = self.escrow.contract.get_disputed_amount(deal_id);
+                let client_share = disputed * u(*client_bps) / u(BPS_DENOMINATOR);
+                let freelancer_share = disputed * u(*freelancer_bps) / u(BPS_DENOMINATOR);
+                self.escrow.sender(ACTORS[*actor]);
+                let contract = &mut self.escrow.contract;
+                let succeeded =
+                    attempt(|| contract.resolve_dispute(deal_id, client_share, freelancer_share));
+                (succeeded, Some(deal_id))
+            }
+            Action::Warp { seconds } => {
+                self.now += seconds;
+                self.escrow.at(self.now);
+                (true, None)
+            }
+        };
+
+        let after = self.snapshot();
+        if succeeded {
+            check_transition(&before, &after, target);
+        } else {
+            assert_eq!(before, after, "reverted {action:?} changed state");
+        }
+        self.check_invariants(&after);
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        let contract = &self.escrow.contract;
+        let deals = (0..self.deals as u64)
+            .map(|id| {
+                let deal_id = u(id);
+                let mut milestones = Vec::new();
+                while let Ok((amount, released, _, _)) =
+                    contract.get_milestone(deal_id, u(milestones.len() as u64))
+                {
+                    let disputed =
+                        contract.is_milestone_disputed(deal_id, u(milestones.len() as u64));
+                    milestones.push((amount, released, disputed));
+                }
+                DealView {
+                    status: self.escrow.status(deal_id),
+                    remaining: contract.get_deal_amount(deal_id),
+                    disputed: contract.get_disputed_amount(deal_id),
+                    milestones,
+                }
+            })
+            .collect();
+
+        Snapshot {
+            deals,
+            escrowed: contract.get_total_escrowed(Address::ZERO),
+            fees: contract.get_accrued_fees(Address::ZERO),
+            logs: self.escrow.vm.get_emitted_logs().len(),
+        }
+    }
+
+    fn check_invariants(&self, snapshot: &Snapshot) {
+        let mut total_remaining = U256::ZERO;
+        for (id, deal) in snapshot.deals.iter().enumerate() {
+            let unreleased: U256 = deal
+                .milestones
+                .iter()
+                .filter(|(_, released, _)| !released)
+                .map(|(amount, _, _)| *amount)
+                .sum();
+            assert_eq!(
+                deal.remaining, unreleased,
+                "deal {id}: remaining amount differs from unreleased milestones"
+            );
+
+            let frozen: U256 = deal
+                .milestones
+                .iter()
+                .filter(|(_, _, disputed)| *disputed)
+                .map(|(amount, _, _)| *amount)
+                .sum();
+            assert_eq!(deal.disputed, frozen, "deal {id}: disputed amount");
+            assert_eq!(
+                deal.status == DISPUTED,
+                deal.disputed > U256::ZERO,
+                "deal {id}: dispute state"
+            );
+            assert_eq!(
+                deal.status == COMPLETED,
+                deal.remaining == U256::ZERO,
+                "deal {id}: completion state"
+            );
+
+            total_remaining += deal.remaining;
+        }
+        assert_eq!(snapshot.escrowed, total_remaining, "escrowed funds");
+
+        // Every milestone is paid out at most once
+        let releases = self.escrow.events::<MilestoneReleased>();
+        let released: HashSet<_> = releases
+            .iter()
+            .map(|e| (e.deal_id, e.milestone_index))
+            .collect();
+        assert_eq!(released.len(), releases.len(), "milestone released twice");
+
+        // Everything deposited is either paid out, still escrowed, or held as fees
+        let paid_out: U256 = releases.iter().map(|e| e.amount).sum::<U256>()
+            + self
+                .escrow
+                .events::<DisputeResolved>()
+                .iter()
+                .map(|e| e.client_amount + e.freelancer_amount + e.arbiter_fee)
+                .sum::<U256>();
+        assert_eq!(
+            self.deposited,
+            paid_out + snapshot.escrowed + snapshot.fees,
+            "funds not conserved"
+        );
+
+        // ...and what the events report as paid out actually reached the actors
+        let received: U256 = ACTORS.iter().map(|a| self.escrow.eth_balance(*a)).sum();
+        assert_eq!(received, paid_out, "payout events differ from ETH received");
+    }
+}
+
+/// Runs a contract call, treating both `Err` and a panic (`require`) as a revert.
+fn attempt<T>(call: impl FnOnce() -> Result<T, Vec<u8>>) -> bool {
+    matches!(catch_unwind(AssertUnwindSafe(call)), Ok(Ok(_)))
+}
+
+/// Status changes a single successful call may cause
+fn is_legal(from: u64, to: u64) -> bool {
+    from == to
+        || matches!(
+            (from, to),
+            (FUNDED, ACTIVE)
+                | (FUNDED | ACTIVE, COMPLETED)
+                | (FUNDED | ACTIVE, DISPUTED)
+                | (DISPUTED, FUNDED | ACTIVE | COMPLETED)
+        )
+}
+
+fn check_transition(before: &Snapshot, after: &Snapshot, target: Option<U256>) {
+    if after.deals.len() > before.deals.len() {
+        assert_eq!(after.deals.len(), before.deals.len() + 1);
+        assert_eq!(after.deals.last().unwrap().status, FUNDED);
+    }
+
+    for (id, (old, new)) in before.deals.iter().zip(after.deals.iter()).enumerate() {
+        if target != Some(u(id as u64)) {
+            assert_eq!(old, new, "deal {id} changed by a call on another deal");
+            continue;
+        }
+
+        assert!(
+            is_legal(old.status, new.status),
+            "deal {id}: illegal transition {} -> {}",
+            old.status,
+            new.status
+        );
+        for (m, (was, is)) in old.milestones.iter().zip(new.milestones.iter()).enumerate() {
+            assert!(!was.1 || is.1, "deal {id}: milestone {m} unreleased");
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(128))]
+
+    #[test]
+    fn random_action_sequences_preserve_invariants(
+        actions in prop::collection::vec(action(), 1..40)
+    ) {
+        let mut fuzzer = Fuzzer::new();
+        for action in &actions {
+            fuzzer.run(action);
+        }
+    }
+}
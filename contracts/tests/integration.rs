@@ -14,8 +14,6 @@ const PRIVATE_KEY: &str = "0xb6b15c8cb491557369f3c7d2c287b053eb229daa9c221388877
 abigen!(
     ArbiSecure,
     r#"[
-         function initialize() external
-         function transferAdmin(address new_admin) external
          struct Milestone { uint256 amount; uint64 end_timestamp; bool is_released; bool requires_approval; }
          function create_deal(uint256 _ref_id, address freelancer, address arbiter, address token, uint256 amount, uint256[] milestone_amounts, uint64[] milestone_end_times, bool[] milestone_approvals) external payable
          function releaseMilestone(uint256 deal_id, uint256 milestone_index) external
@@ -26,6 +24,7 @@ abigen!(
 );
 
 #[tokio::test]
+#[ignore = "requires a local Nitro node"]
 async fn test_integration_local() -> Result<(), Box<dyn std::error::Error>> {
     let provider = Provider::<Http>::try_from(RPC_URL)?;
     let wallet: LocalWallet = PRIVATE_KEY.parse()?;
@@ -38,33 +37,21 @@ async fn test_integration_local() -> Result<(), Box<dyn std::error::Error>> {
     let address: Address = CONTRACT_ADDRESS.parse()?;
     let contract = ArbiSecure::new(address, client.clone());
 
-    println!("Connected to contract at: {:?}", address);
-
-    // 1. Initialize (should succeed or fail if already initialized)
-    println!("Calling initialize...");
-    let tx = contract.initialize();
-    let pending_tx = tx.send().await;
-    match pending_tx {
-        Ok(receipt) => {
-            let _ = receipt.await?;
-            println!("Initialize success");
-        }
-        Err(e) => println!("Initialize failed (might be already initialized): {:?}", e),
-    }
-
-    // Check Admin (First time)
+    println!("Connected to contract at: {address:?}");
+
+    // 1. The constructor sets the admin at deployment (see deploy_local.sh)
     let current_admin = contract.admin().call().await?;
-    println!("Current Admin: {:?}", current_admin);
+    println!("Current Admin: {current_admin:?}");
 
     // 2. Transfer Admin - SKIPPED to avoid permission errors on re-runs or fresh deploy confusion
     // let new_admin = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8".parse::<Address>()?; // Using typical Anvil/Nitro account 1
-    // println!("Calling transferAdmin to {:?}", new_admin);
+    // println!("Calling transferAdmin to {new_admin:?}");
     // let tx = contract.transfer_admin(new_admin);
     // let receipt = tx.send().await?.await?;
-    // println!("Transfer Admin Receipt: {:?}", receipt);
+    // println!("Transfer Admin Receipt: {receipt:?}");
 
     let current_admin = contract.admin().call().await?;
-    println!("New Admin: {:?}", current_admin);
+    println!("New Admin: {current_admin:?}");
 
     // --- Scenario A: Happy Path (Create -> Release) ---
     println!("\n--- Scenario A: Happy Path ---");
@@ -104,7 +91,7 @@ async fn test_integration_local() -> Result<(), Box<dyn std::error::Error>> {
     let call = contract.release_milestone(deal_id_a, U256::zero());
     let pending_tx = call.send().await?;
     let receipt = pending_tx.await?;
-    println!("Release Milestone Receipt: {:?}", receipt);
+    println!("Release Milestone Receipt: {receipt:?}");
 
     // --- Scenario B: Dispute Path (Create -> Dispute -> Resolve) ---
     println!("\n--- Scenario B: Dispute Path ---");
@@ -136,7 +123,7 @@ async fn test_integration_local() -> Result<(), Box<dyn std::error::Error>> {
     let call = contract.raise_dispute(deal_id_b);
     let pending_tx = call.send().await?;
     let receipt = pending_tx.await?;
-    println!("Dispute Raised Receipt: {:?}", receipt);
+    println!("Dispute Raised Receipt: {receipt:?}");
 
     // Resolve Dispute (as Arbiter)
     // NOTE: The arbiter address is "0x7099..." which is likely the account we transferred admin to?
@@ -149,10 +136,7 @@ async fn test_integration_local() -> Result<(), Box<dyn std::error::Error>> {
     // For simplicity, let's use Account 0 as arbiter for Deal B so we don't need multi-wallet setup in this test.
 
     let our_address = wallet.address();
-    println!(
-        "Using our address {:?} as Arbiter for Deal C to simplify testing...",
-        our_address
-    );
+    println!("Using our address {our_address:?} as Arbiter for Deal C to simplify testing...");
 
     println!("\n--- Scenario C: Dispute Path (Self-Arbiter) ---");
     let call = contract
@@ -193,7 +177,7 @@ async fn test_integration_local() -> Result<(), Box<dyn std::error::Error>> {
     let call = contract.resolve_dispute(deal_id_c, split, split);
     let pending_tx = call.send().await?;
     let receipt = pending_tx.await?;
-    println!("Dispute Resolved Receipt: {:?}", receipt);
+    println!("Dispute Resolved Receipt: {receipt:?}");
 
     Ok(())
 }
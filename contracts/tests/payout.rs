@@ -0,0 +1,194 @@
+use alloy_primitives::U256;
+use arbisecure_contracts::{split_dispute_payout, FeeMode, ARBITER_FEE_BPS, BPS_DENOMINATOR};
+use proptest::prelude::*;
+
+/// Disputed amount and a ruling that allocates at most that amount.
+fn ruling() -> impl Strategy<Value = (u128, u128, u128)> {
+    any::<u128>()
+        .prop_flat_map(|total| (Just(total), 0..=total))
+        .prop_flat_map(|(total, client)| (Just(total), Just(client), 0..=total - client))
+}
+
+proptest! {
+    #[test]
+    fn payout_distributes_entire_disputed_amount((total, client, freelancer) in ruling()) {
+        let payout = split_dispute_payout(
+            U256::from(total),
+            U256::from(client),
+            U256::from(freelancer),
+            U256::from(ARBITER_FEE_BPS),
+            FeeMode::Proportional,
+        )
+        .unwrap();
+
+        prop_assert_eq!(
+            payout.net_client + payout.net_freelancer + payout.fee,
+            U256::from(total)
+        );
+    }
+
+    #[test]
+    fn fee_matches_rate_on_disputed_amount((total, client, freelancer) in ruling()) {
+        let payout = split_dispute_payout(
+            U256::from(total),
+            U256::from(client),
+            U256::from(freelancer),
+            U256::from(ARBITER_FEE_BPS),
+            FeeMode::Proportional,
+        )
+        .unwrap();
+
+        let expected = U256::from(total) * U256::from(ARBITER_FEE_BPS) / U256::from(BPS_DENOMINATOR);
+        prop_assert_eq!(payout.fee, expected);
+    }
+
+    #[test]
+    fn unallocated_remainder_goes_to_client((total, client, freelancer) in ruling()) {
+        let payout = split_dispute_payout(
+            U256::from(total),
+            U256::from(client),
+            U256::from(freelancer),
+            U256::from(ARBITER_FEE_BPS),
+            FeeMode::Proportional,
+        )
+        .unwrap();
+
+        // Neither party receives more than they were awarded (client includes the remainder)
+        prop_assert!(payout.net_freelancer <= U256::from(freelancer));
+        prop_assert!(payout.net_client <= U256::from(total - freelancer));
+        // Deductions never exceed the fee rate by more than one unit of rounding dust
+        let max_freelancer_fee =
+            U256::from(freelancer) * U256::from(ARBITER_FEE_BPS) / U256::from(BPS_DENOMINATOR)
+                + U256::from(1);
+        prop_assert!(U256::from(freelancer) - payout.net_freelancer <= max_freelancer_fee);
+    }
+
+    #[test]
+    fn any_fee_rate_conserves_funds(
+        (total, client, freelancer) in ruling(),
+        fee_bps in 0..=BPS_DENOMINATOR,
+    ) {
+        let payout = split_dispute_payout(
+            U256::from(total),
+            U256::from(client),
+            U256::from(freelancer),
+            U256::from(fee_bps),
+            FeeMode::Proportional,
+        )
+        .unwrap();
+
+        prop_assert_eq!(
+            payout.net_client + payout.net_freelancer + payout.fee,
+            U256::from(total)
+        );
+    }
+
+    #[test]
+    fn every_fee_mode_conserves_funds(
+        (total, client, freelancer) in ruling(),
+        mode in 0u8..3,
+    ) {
+        let fee_mode = FeeMode::from_u8(mode).unwrap();
+        let payout = split_dispute_payout(
+            U256::from(total),
+            U256::from(client),
+            U256::from(freelancer),
+            U256::from(ARBITER_FEE_BPS),
+            fee_mode,
+        )
+        .unwrap();
+
+        prop_assert_eq!(
+            payout.net_client + payout.net_freelancer + payout.fee,
+            U256::from(total)
+        );
+        prop_assert!(payout.net_freelancer <= U256::from(freelancer));
+    }
+}
+
+#[test]
+fn even_split_of_odd_amount_loses_no_dust() {
+    let payout = split_dispute_payout(
+        U256::from(199),
+        U256::from(99),
+        U256::from(99),
+        U256::from(ARBITER_FEE_BPS),
+        FeeMode::Proportional,
+    )
+    .unwrap();
+
+    // 1 wei left unallocated is refunded to the client
+    assert_eq!(payout.fee, U256::from(9));
+    assert_eq!(payout.net_client, U256::from(100 - 5));
+    assert_eq!(payout.net_freelancer, U256::from(99 - 4));
+}
+
+#[test]
+fn loser_pays_the_whole_fee() {
+    let payout = split_dispute_payout(
+        U256::from(1000),
+        U256::from(200),
+        U256::from(800),
+        U256::from(ARBITER_FEE_BPS),
+        FeeMode::LoserPays,
+    )
+    .unwrap();
+
+    assert_eq!(payout.fee, U256::from(50));
+    assert_eq!(payout.net_client, U256::from(150));
+    assert_eq!(payout.net_freelancer, U256::from(800));
+}
+
+#[test]
+fn client_pays_even_when_awarded_more() {
+    let payout = split_dispute_payout(
+        U256::from(1000),
+        U256::from(800),
+        U256::from(200),
+        U256::from(ARBITER_FEE_BPS),
+        FeeMode::ClientPays,
+    )
+    .unwrap();
+
+    assert_eq!(payout.net_client, U256::from(750));
+    assert_eq!(payout.net_freelancer, U256::from(200));
+}
+
+#[test]
+fn fee_beyond_payer_award_is_topped_up_by_the_other_party() {
+    let payout = split_dispute_payout(
+        U256::from(1000),
+        U256::from(20),
+        U256::from(980),
+        U256::from(ARBITER_FEE_BPS),
+        FeeMode::ClientPays,
+    )
+    .unwrap();
+
+    assert_eq!(payout.net_client, U256::ZERO);
+    assert_eq!(payout.net_freelancer, U256::from(950));
+}
+
+#[test]
+fn oversized_ruling_reverts_instead_of_wrapping() {
+    let result = split_dispute_payout(
+        U256::from(100),
+        U256::from(60),
+        U256::from(60),
+        U256::from(ARBITER_FEE_BPS),
+        FeeMode::Proportional,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn fee_overflow_reverts_instead_of_wrapping() {
+    let result = split_dispute_payout(
+        U256::MAX,
+        U256::ZERO,
+        U256::MAX,
+        U256::from(ARBITER_FEE_BPS),
+        FeeMode::Proportional,
+    );
+    assert!(result.is_err());
+}
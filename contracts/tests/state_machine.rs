@@ -0,0 +1,366 @@
+use arbisecure_contracts::state_machine::{transition, Action, Role, TransitionError};
+use arbisecure_contracts::DealStatus::{self, *};
+
+const STATUSES: [DealStatus; 6] = [Created, Funded, Active, Disputed, Completed, Cancelled];
+
+const ROLES: [Role; 5] = [
+    Role::Client,
+    Role::Freelancer,
+    Role::Arbiter,
+    Role::Court,
+    Role::Other,
+];
+
+const ACTIONS: [Action; 22] = [
+    Action::Create,
+    Action::Release { completes: false },
+    Action::Release { completes: true },
+    Action::RaiseDispute,
+    Action::Rule,
+    Action::Appeal,
+    Action::RuleAppeal,
+    Action::Settle { completes: false },
+    Action::Settle { completes: true },
+    Action::ReassignArbiter,
+    Action::Recuse,
+    Action::DrawArbiter,
+    Action::ProposeSettlement,
+    Action::AcceptSettlement,
+    Action::WithdrawDispute { resumes: Funded },
+    Action::WithdrawDispute { resumes: Active },
+    Action::MatchBond,
+    Action::Fund,
+    Action::EndRetainer { completes: false },
+    Action::EndRetainer { completes: true },
+    Action::CancelStream { completes: false },
+    Action::CancelStream { completes: true },
+];
+
+#[test]
+fn create_funds_new_deal() {
+    assert_eq!(
+        transition(Created, Action::Create, Role::Client),
+        Ok(Funded)
+    );
+    for status in [Funded, Active, Disputed, Completed, Cancelled] {
+        assert_eq!(
+            transition(status, Action::Create, Role::Client),
+            Err(TransitionError::InvalidStatus)
+        );
+    }
+}
+
+#[test]
+fn release_moves_to_active_or_completed() {
+    for status in [Funded, Active] {
+        for role in ROLES {
+            assert_eq!(
+                transition(status, Action::Release { completes: false }, role),
+                Ok(Active)
+            );
+            assert_eq!(
+                transition(status, Action::Release { completes: true }, role),
+                Ok(Completed)
+            );
+        }
+    }
+}
+
+#[test]
+fn release_during_dispute_keeps_it_open() {
+    assert_eq!(
+        transition(Disputed, Action::Release { completes: false }, Role::Client),
+        Ok(Disputed)
+    );
+    // Disputed funds are always left in escrow, so a release can never complete the deal
+    assert_eq!(
+        transition(Disputed, Action::Release { completes: true }, Role::Client),
+        Err(TransitionError::InvalidStatus)
+    );
+}
+
+#[test]
+fn only_parties_raise_disputes() {
+    for status in [Funded, Active] {
+        assert_eq!(
+            transition(status, Action::RaiseDispute, Role::Client),
+            Ok(Disputed)
+        );
+        assert_eq!(
+            transition(status, Action::RaiseDispute, Role::Freelancer),
+            Ok(Disputed)
+        );
+        for role in [Role::Arbiter, Role::Court, Role::Other] {
+            assert_eq!(
+                transition(status, Action::RaiseDispute, role),
+                Err(TransitionError::Unauthorized)
+            );
+        }
+    }
+    assert_eq!(
+        transition(Disputed, Action::RaiseDispute, Role::Client),
+        Err(TransitionError::InvalidStatus)
+    );
+}
+
+#[test]
+fn rulings_require_the_right_role() {
+    assert_eq!(
+        transition(Disputed, Action::Rule, Role::Arbiter),
+        Ok(Disputed)
+    );
+    assert_eq!(
+        transition(Disputed, Action::Rule, Role::Client),
+        Err(TransitionError::Unauthorized)
+    );
+    assert_eq!(
+        transition(Disputed, Action::RuleAppeal, Role::Court),
+        Ok(Disputed)
+    );
+    assert_eq!(
+        transition(Disputed, Action::RuleAppeal, Role::Arbiter),
+        Err(TransitionError::Unauthorized)
+    );
+    assert_eq!(
+        transition(Disputed, Action::Appeal, Role::Freelancer),
+        Ok(Disputed)
+    );
+    assert_eq!(
+        transition(Disputed, Action::Appeal, Role::Other),
+        Err(TransitionError::Unauthorized)
+    );
+    for action in [Action::Rule, Action::Appeal, Action::RuleAppeal] {
+        assert_eq!(
+            transition(Active, action, Role::Arbiter),
+            Err(TransitionError::InvalidStatus)
+        );
+    }
+}
+
+#[test]
+fn settlement_resumes_or_completes() {
+    for role in ROLES {
+        assert_eq!(
+            transition(Disputed, Action::Settle { completes: false }, role),
+            Ok(Active)
+        );
+        assert_eq!(
+            transition(Disputed, Action::Settle { completes: true }, role),
+            Ok(Completed)
+        );
+    }
+    assert_eq!(
+        transition(Active, Action::Settle { completes: false }, Role::Arbiter),
+        Err(TransitionError::InvalidStatus)
+    );
+}
+
+#[test]
+fn arbiter_changes_keep_status() {
+    for status in [Funded, Active, Disputed] {
+        assert_eq!(
+            transition(status, Action::ReassignArbiter, Role::Client),
+            Ok(status)
+        );
+        assert_eq!(
+            transition(status, Action::DrawArbiter, Role::Freelancer),
+            Ok(status)
+        );
+        assert_eq!(
+            transition(status, Action::Recuse, Role::Arbiter),
+            Ok(status)
+        );
+    }
+    assert_eq!(
+        transition(Active, Action::Recuse, Role::Client),
+        Err(TransitionError::Unauthorized)
+    );
+    assert_eq!(
+        transition(Active, Action::ReassignArbiter, Role::Arbiter),
+        Err(TransitionError::Unauthorized)
+    );
+}
+
+#[test]
+fn parties_settle_from_any_open_status() {
+    for status in [Funded, Active, Disputed] {
+        for role in [Role::Client, Role::Freelancer] {
+            assert_eq!(
+                transition(status, Action::ProposeSettlement, role),
+                Ok(status)
+            );
+            assert_eq!(
+                transition(status, Action::AcceptSettlement, role),
+                Ok(Completed)
+            );
+        }
+        for role in [Role::Arbiter, Role::Court, Role::Other] {
+            assert_eq!(
+                transition(status, Action::AcceptSettlement, role),
+                Err(TransitionError::Unauthorized)
+            );
+        }
+    }
+}
+
+#[test]
+fn withdrawn_disputes_resume_the_prior_status() {
+    for resumes in [Funded, Active] {
+        for role in [Role::Client, Role::Freelancer] {
+            assert_eq!(
+                transition(Disputed, Action::WithdrawDispute { resumes }, role),
+                Ok(resumes)
+            );
+        }
+        assert_eq!(
+            transition(Active, Action::WithdrawDispute { resumes }, Role::Client),
+            Err(TransitionError::InvalidStatus)
+        );
+        assert_eq!(
+            transition(Disputed, Action::WithdrawDispute { resumes }, Role::Arbiter),
+            Err(TransitionError::Unauthorized)
+        );
+    }
+    // A dispute can only be withdrawn to an open status
+    assert_eq!(
+        transition(
+            Disputed,
+            Action::WithdrawDispute { resumes: Completed },
+            Role::Client
+        ),
+        Err(TransitionError::InvalidStatus)
+    );
+}
+
+#[test]
+fn counterparty_matches_bond_during_dispute() {
+    assert_eq!(
+        transition(Disputed, Action::MatchBond, Role::Freelancer),
+        Ok(Disputed)
+    );
+    assert_eq!(
+        transition(Disputed, Action::MatchBond, Role::Arbiter),
+        Err(TransitionError::Unauthorized)
+    );
+    assert_eq!(
+        transition(Active, Action::MatchBond, Role::Client),
+        Err(TransitionError::InvalidStatus)
+    );
+}
+
+#[test]
+fn retainers_are_funded_by_the_client_and_ended_by_either_party() {
+    for status in [Funded, Active, Disputed] {
+        assert_eq!(transition(status, Action::Fund, Role::Client), Ok(status));
+        assert_eq!(
+            transition(status, Action::Fund, Role::Freelancer),
+            Err(TransitionError::Unauthorized)
+        );
+        assert_eq!(
+            transition(
+                status,
+                Action::EndRetainer { completes: false },
+                Role::Freelancer
+            ),
+            Ok(status)
+        );
+    }
+    assert_eq!(
+        transition(
+            Active,
+            Action::EndRetainer { completes: true },
+            Role::Client
+        ),
+        Ok(Completed)
+    );
+    // Disputed funds are never refunded by an ending
+    assert_eq!(
+        transition(
+            Disputed,
+            Action::EndRetainer { completes: true },
+            Role::Client
+        ),
+        Err(TransitionError::InvalidStatus)
+    );
+}
+
+#[test]
+fn streams_are_cancelled_by_either_party() {
+    for role in [Role::Client, Role::Freelancer] {
+        assert_eq!(
+            transition(Funded, Action::CancelStream { completes: true }, role),
+            Ok(Completed)
+        );
+        assert_eq!(
+            transition(Active, Action::CancelStream { completes: false }, role),
+            Ok(Active)
+        );
+    }
+    assert_eq!(
+        transition(
+            Disputed,
+            Action::CancelStream { completes: false },
+            Role::Client
+        ),
+        Ok(Disputed)
+    );
+    assert_eq!(
+        transition(
+            Active,
+            Action::CancelStream { completes: true },
+            Role::Arbiter
+        ),
+        Err(TransitionError::Unauthorized)
+    );
+}
+
+#[test]
+fn closed_deals_accept_no_actions() {
+    for status in [Completed, Cancelled] {
+        for action in ACTIONS {
+            for role in ROLES {
+                assert_eq!(
+                    transition(status, action, role),
+                    Err(TransitionError::InvalidStatus),
+                    "{status:?} {action:?} {role:?}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn every_transition_follows_a_lifecycle_edge() {
+    let edges = [
+        (Created, Funded),
+        (Funded, Active),
+        (Funded, Disputed),
+        (Funded, Completed),
+        (Active, Disputed),
+        (Active, Completed),
+        (Disputed, Funded),
+        (Disputed, Active),
+        (Disputed, Completed),
+    ];
+
+    for status in STATUSES {
+        for action in ACTIONS {
+            for role in ROLES {
+                if let Ok(next) = transition(status, action, role) {
+                    assert!(
+                        next == status || edges.contains(&(status, next)),
+                        "{status:?} -> {next:?} via {action:?} by {role:?}"
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn status_is_checked_before_role() {
+    assert_eq!(
+        transition(Completed, Action::Rule, Role::Other),
+        Err(TransitionError::InvalidStatus)
+    );
+}
@@ -0,0 +1,178 @@
+mod common;
+
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::SolValue;
+use arbisecure_contracts::{
+    OperationCancelled, OperationExecuted, OperationKind, OperationScheduled,
+    DEFAULT_TIMELOCK_DELAY, TOKEN_LISTER_ROLE,
+};
+use common::*;
+
+const NOW: u64 = 1_000;
+const ETA: u64 = NOW + DEFAULT_TIMELOCK_DELAY;
+
+#[test]
+fn scheduling_announces_the_change() {
+    let mut escrow = Escrow::new();
+    escrow.sender(ADMIN);
+    let operation_id = escrow.contract.schedule_arbiter_registry(REGISTRY).unwrap();
+
+    let (kind, eta, params) = escrow.contract.get_operation(operation_id);
+    assert_eq!(kind, OperationKind::SetArbiterRegistry.as_u8());
+    assert_eq!(eta, ETA);
+    assert_eq!(params.to_vec(), (REGISTRY,).abi_encode_params());
+
+    let scheduled = escrow.events::<OperationScheduled>();
+    let last = scheduled.last().unwrap();
+    assert_eq!(last.operation_id, operation_id);
+    assert_eq!(last.kind, OperationKind::SetArbiterRegistry.as_u8());
+    assert_eq!(last.eta, ETA);
+
+    // Nothing changes until the operation executes
+    assert_eq!(escrow.contract.arbiter_registry(), Address::ZERO);
+}
+
+#[test]
+fn anyone_executes_after_the_delay() {
+    let mut escrow = Escrow::new();
+    escrow.sender(ADMIN);
+    let operation_id = escrow
+        .contract
+        .schedule_appeal_config(u(3600), u(1000), COURT)
+        .unwrap();
+
+    escrow.at(ETA).sender(STRANGER);
+    escrow.contract.execute_operation(operation_id).unwrap();
+    assert_eq!(
+        escrow.contract.get_appeal_config(),
+        (u(3600), u(1000), COURT)
+    );
+    assert_eq!(
+        escrow
+            .events::<OperationExecuted>()
+            .last()
+            .unwrap()
+            .operation_id,
+        operation_id
+    );
+    assert_eq!(escrow.contract.get_operation(operation_id).1, 0);
+}
+
+#[test]
+#[should_panic]
+fn execution_waits_for_the_eta() {
+    let mut escrow = Escrow::new();
+    escrow.sender(ADMIN);
+    let operation_id = escrow.contract.schedule_arbiter_registry(REGISTRY).unwrap();
+
+    escrow.at(ETA - 1);
+    let _ = escrow.contract.execute_operation(operation_id);
+}
+
+#[test]
+#[should_panic]
+fn operations_execute_once() {
+    let mut escrow = Escrow::new();
+    escrow.sender(ADMIN);
+    let operation_id = escrow.contract.schedule_arbiter_registry(REGISTRY).unwrap();
+
+    escrow.at(ETA);
+    escrow.contract.execute_operation(operation_id).unwrap();
+    let _ = escrow.contract.execute_operation(operation_id);
+}
+
+#[test]
+#[should_panic]
+fn cancelled_operation_cannot_execute() {
+    let mut escrow = Escrow::new();
+    escrow.sender(ADMIN);
+    let operation_id = escrow.contract.schedule_arbiter_registry(REGISTRY).unwrap();
+    escrow.contract.cancel_operation(operation_id);
+    assert_eq!(
+        escrow.events::<OperationCancelled>()[0].operation_id,
+        operation_id
+    );
+
+    escrow.at(ETA);
+    let _ = escrow.contract.execute_operation(operation_id);
+}
+
+#[test]
+#[should_panic]
+fn cancel_requires_scheduling_role() {
+    let mut escrow = Escrow::new();
+    escrow.sender(ADMIN);
+    let operation_id = escrow.contract.schedule_arbiter_registry(REGISTRY).unwrap();
+
+    escrow.sender(STRANGER);
+    escrow.contract.cancel_operation(operation_id);
+}
+
+#[test]
+fn token_lister_schedules_listings() {
+    let mut escrow = Escrow::new();
+    escrow.sender(ADMIN);
+    escrow.contract.grant_role(TOKEN_LISTER_ROLE, STRANGER);
+
+    let listed = Address::repeat_byte(0x71);
+    escrow.sender(STRANGER);
+    let operation_id = escrow
+        .contract
+        .schedule_token_allowed(listed, true)
+        .unwrap();
+    assert!(!escrow.contract.is_token_allowed(listed));
+
+    escrow.execute_after_delay(operation_id);
+    assert!(escrow.contract.is_token_allowed(listed));
+}
+
+#[test]
+fn delay_changes_through_the_timelock() {
+    let mut escrow = Escrow::new();
+    assert_eq!(escrow.contract.timelock_delay(), DEFAULT_TIMELOCK_DELAY);
+
+    escrow.sender(ADMIN);
+    let operation_id = escrow.contract.schedule_timelock_delay(3 * 86_400).unwrap();
+    escrow.execute_after_delay(operation_id);
+    assert_eq!(escrow.contract.timelock_delay(), 3 * 86_400);
+
+    let operation_id = escrow.contract.schedule_arbiter_registry(REGISTRY).unwrap();
+    assert_eq!(
+        escrow.contract.get_operation(operation_id).1,
+        NOW + 3 * 86_400
+    );
+}
+
+#[test]
+#[should_panic]
+fn delay_is_bounded() {
+    let mut escrow = Escrow::new();
+    escrow.sender(ADMIN);
+    escrow.contract.schedule_timelock_delay(60).unwrap();
+}
+
+#[test]
+#[should_panic]
+fn dispute_bond_is_flat_or_proportional() {
+    let mut escrow = Escrow::new();
+    escrow.sender(ADMIN);
+    escrow
+        .contract
+        .schedule_dispute_bond(Address::ZERO, u(100), u(500))
+        .unwrap();
+}
+
+#[test]
+fn pending_operations_do_not_block_deals() {
+    let mut escrow = Escrow::new();
+    escrow.sender(ADMIN);
+    escrow
+        .contract
+        .schedule_appeal_config(u(3600), u(1000), COURT)
+        .unwrap();
+
+    let deal_id = escrow.eth_deal(&[1000]);
+    assert_eq!(escrow.contract.get_total_escrowed(Address::ZERO), u(1000));
+    assert_eq!(escrow.contract.get_deal_amount(deal_id), u(1000));
+    assert_eq!(escrow.contract.get_appeal_config().0, U256::ZERO);
+}
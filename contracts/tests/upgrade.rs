@@ -0,0 +1,186 @@
+extern crate alloc;
+
+mod common;
+
+use alloy_primitives::{Address, B256, U256};
+use alloy_sol_types::{sol, SolCall};
+use arbisecure_contracts::{
+    ArbiSecure, Migrated, TokenListed, Upgraded, DEFAULT_TIMELOCK_DELAY, FEE_MANAGER_ROLE,
+    PAUSER_ROLE, STORAGE_VERSION, TOKEN_LISTER_ROLE,
+};
+use common::*;
+use stylus_sdk::{prelude::*, testing::*};
+
+const V2: Address = Address::repeat_byte(0x22);
+
+/// Slots of `admin`, `storage_version` and `allowed_tokens` in the `ArbiSecure` layout
+const ADMIN_SLOT: u64 = 0;
+const STORAGE_VERSION_SLOT: u64 = 10;
+const ALLOWED_TOKENS_SLOT: u64 = 13;
+
+/// Slot of `allowed_tokens[token]`
+fn allowed_token_slot(token: Address) -> U256 {
+    let mut data = token.into_word().to_vec();
+    data.extend_from_slice(&u(ALLOWED_TOKENS_SLOT).to_be_bytes::<32>());
+    U256::from_be_bytes(alloy_primitives::keccak256(&data).0)
+}
+
+sol! {
+    interface IMigrate {
+        function migrate() external;
+    }
+}
+
+sol_storage! {
+    /// Next storage layout: the complete v1 layout followed by a newly appended field
+    pub struct ArbiSecureV2 {
+        ArbiSecure v1;
+        uint256 resolved_disputes;
+    }
+}
+
+#[test]
+fn initialize_records_storage_version() {
+    let escrow = Escrow::new();
+    assert_eq!(escrow.contract.storage_version(), STORAGE_VERSION);
+}
+
+#[test]
+fn initialize_sets_up_fresh_proxy_storage() {
+    // Stands in for the proxy's storage, which the implementation's constructor never touches
+    let vm = TestVM::default();
+    let mut contract = ArbiSecure::from(&vm);
+    vm.set_sender(STRANGER);
+    contract.initialize(ADMIN);
+
+    assert_eq!(contract.admin(), ADMIN);
+    assert!(contract.has_role(PAUSER_ROLE, ADMIN));
+    assert_eq!(contract.storage_version(), STORAGE_VERSION);
+}
+
+#[test]
+#[should_panic]
+fn upgrade_requires_admin() {
+    let mut escrow = Escrow::new();
+    escrow.sender(STRANGER);
+    escrow.contract.schedule_upgrade(V2, Vec::new()).unwrap();
+}
+
+#[test]
+#[should_panic]
+fn migrate_rejects_current_layout() {
+    let mut escrow = Escrow::new();
+    escrow.sender(ADMIN);
+    escrow.contract.migrate();
+}
+
+#[test]
+fn deals_survive_upgrade_to_v2() {
+    let mut escrow = Escrow::new();
+    let first = escrow.eth_deal(&[600, 400]);
+    let second = escrow.eth_deal(&[1000]);
+
+    escrow.sender(CLIENT);
+    escrow.contract.release_milestone(first, u(0)).unwrap();
+    assert_eq!(escrow.eth_balance(FREELANCER), u(597));
+    escrow.contract.raise_dispute(second).unwrap();
+
+    escrow.sender(ADMIN);
+    let operation_id = escrow.contract.schedule_upgrade(V2, Vec::new()).unwrap();
+    escrow.execute_after_delay(operation_id);
+    assert_eq!(escrow.contract.implementation(), V2);
+    assert_eq!(escrow.events::<Upgraded>()[0].implementation, V2);
+
+    // The v2 implementation reads the v1 deals from the same storage
+    let mut v2 = ArbiSecureV2::from(&escrow.vm);
+    assert_eq!(v2.v1.admin(), ADMIN);
+    assert_eq!(v2.v1.get_deal_client(first), CLIENT);
+    assert_eq!(v2.v1.get_deal_freelancer(first), FREELANCER);
+    assert_eq!(v2.v1.get_deal_arbiter(first), ARBITER);
+    assert_eq!(v2.v1.get_deal_amount(first), u(400));
+    assert_eq!(v2.v1.get_deal_status(first), u(ACTIVE));
+    assert_eq!(
+        v2.v1.get_milestone(first, u(0)).unwrap(),
+        (u(600), true, U256::ZERO, false)
+    );
+    assert_eq!(v2.v1.get_deal_status(second), u(DISPUTED));
+    assert_eq!(v2.v1.get_disputed_amount(second), u(1000));
+    assert_eq!(v2.v1.get_total_escrowed(Address::ZERO), u(1400));
+    assert_eq!(v2.resolved_disputes.get(), U256::ZERO);
+
+    // ...and both deals run to completion under it
+    escrow.sender(FREELANCER);
+    v2.v1.release_milestone(first, u(1)).unwrap();
+    assert_eq!(escrow.eth_balance(FREELANCER), u(995));
+    assert_eq!(v2.v1.get_deal_status(first), u(COMPLETED));
+
+    escrow.sender(ARBITER);
+    v2.v1.resolve_dispute(second, u(1000), U256::ZERO).unwrap();
+    assert_eq!(escrow.eth_balance(CLIENT), u(950));
+    assert_eq!(escrow.eth_balance(ARBITER), u(50));
+    assert_eq!(v2.v1.get_deal_status(second), u(COMPLETED));
+
+    assert_eq!(v2.v1.get_total_escrowed(Address::ZERO), U256::ZERO);
+    assert_eq!(v2.v1.get_accrued_fees(Address::ZERO), u(5));
+}
+
+#[test]
+fn upgrade_hook_migrates_v1_storage() {
+    // Storage as a v1 deployment left it: an admin, but no roles and no timelock delay
+    let vm = TestVM::default();
+    vm.set_contract_address(ESCROW);
+    vm.set_block_timestamp(1_000);
+    vm.set_storage(u(ADMIN_SLOT), ADMIN.into_word());
+    vm.set_storage(u(STORAGE_VERSION_SLOT), B256::from(u(1)));
+    let mut escrow = Escrow {
+        vm: vm.clone(),
+        contract: ArbiSecure::from(&vm),
+    };
+    assert_eq!(escrow.contract.admin(), ADMIN);
+    assert_eq!(escrow.contract.storage_version(), 1);
+    assert!(!escrow.contract.has_role(PAUSER_ROLE, ADMIN));
+    assert_eq!(escrow.contract.timelock_delay(), 0);
+
+    let deal_id = escrow.eth_deal(&[1000]);
+
+    // v1 accepted any token; list it only for as long as it takes to open the deal
+    vm.set_storage(allowed_token_slot(TOKEN), B256::from(u(1)));
+    let token_deal = escrow.deal_with(ARBITER, TOKEN, &[500], Vec::new());
+    vm.set_storage(allowed_token_slot(TOKEN), B256::ZERO);
+    assert!(!escrow.contract.is_token_allowed(TOKEN));
+
+    // With no delay yet, the upgrade executes right away and calls migrate() on V2
+    let hook = IMigrate::migrateCall {}.abi_encode();
+    vm.mock_delegate_call(V2, hook.clone(), Ok(Vec::new()));
+    escrow.sender(ADMIN);
+    let operation_id = escrow.contract.schedule_upgrade(V2, hook).unwrap();
+    escrow.contract.execute_operation(operation_id).unwrap();
+    assert_eq!(escrow.contract.implementation(), V2);
+
+    // TestVM does not run delegate calls, so run the hook against the same storage
+    let mut v2 = ArbiSecureV2::from(&vm);
+    v2.v1.migrate();
+
+    let migrated = &escrow.events::<Migrated>()[0];
+    assert_eq!(
+        (migrated.from_version, migrated.to_version),
+        (1, STORAGE_VERSION)
+    );
+    assert_eq!(v2.v1.storage_version(), STORAGE_VERSION);
+    for role in [FEE_MANAGER_ROLE, PAUSER_ROLE, TOKEN_LISTER_ROLE] {
+        assert!(v2.v1.has_role(role, ADMIN));
+    }
+    assert_eq!(v2.v1.timelock_delay(), DEFAULT_TIMELOCK_DELAY);
+    // Tokens of existing deals stay accepted for new ones
+    assert!(v2.v1.is_token_allowed(TOKEN));
+    assert!(!v2.v1.is_token_allowed(STRANGER));
+    let listed = escrow.events::<TokenListed>();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].token, TOKEN);
+
+    // Deals opened under v1 carry on under the migrated layout
+    assert_eq!(v2.v1.get_deal_client(deal_id), CLIENT);
+    assert_eq!(v2.v1.get_deal_amount(deal_id), u(1000));
+    assert_eq!(v2.v1.get_deal_status(deal_id), u(FUNDED));
+    assert_eq!(v2.v1.get_deal_amount(token_deal), u(500));
+}
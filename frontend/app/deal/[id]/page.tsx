@@ -154,7 +154,7 @@ export default function DealPage() {
             try {
                 const logs = await publicClient.getLogs({
                     address: CONTRACT_ADDRESS,
-                    event: parseAbiItem('event DisputeResolved(uint256 indexed deal_id, uint256 client_amount, uint256 freelancer_amount, uint256 arbiter_fee)'),
+                    event: parseAbiItem('event DisputeResolved(uint256 indexed deal_id, uint256 client_amount, uint256 freelancer_amount, uint256 arbiter_fee, uint8 fee_mode)'),
                     args: { deal_id: dealId },
                     fromBlock: 0n // Search from genesis/deployment
                 });
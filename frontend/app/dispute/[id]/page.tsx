@@ -137,7 +137,7 @@ export default function DisputePage() {
             try {
                 const logs = await publicClient.getLogs({
                     address: CONTRACT_ADDRESS,
-                    event: parseAbiItem('event DisputeResolved(uint256 indexed deal_id, uint256 client_amount, uint256 freelancer_amount, uint256 arbiter_fee)'),
+                    event: parseAbiItem('event DisputeResolved(uint256 indexed deal_id, uint256 client_amount, uint256 freelancer_amount, uint256 arbiter_fee, uint8 fee_mode)'),
                     args: { deal_id: dealId },
                     fromBlock: 0n
                 });
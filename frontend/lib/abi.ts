@@ -1,11 +1,4 @@
 export const ARBISECURE_ABI = [
-    {
-        "type": "function",
-        "name": "initialize",
-        "inputs": [],
-        "outputs": [],
-        "stateMutability": "nonpayable"
-    },
     {
         "type": "function",
         "name": "createDeal",
@@ -131,7 +124,8 @@ export const ARBISECURE_ABI = [
             { "name": "deal_id", "type": "uint256", "indexed": true },
             { "name": "client_amount", "type": "uint256", "indexed": false },
             { "name": "freelancer_amount", "type": "uint256", "indexed": false },
-            { "name": "arbiter_fee", "type": "uint256", "indexed": false }
+            { "name": "arbiter_fee", "type": "uint256", "indexed": false },
+            { "name": "fee_mode", "type": "uint8", "indexed": false }
         ],
         "anonymous": false
     },
@@ -24,7 +24,7 @@ async function main() {
 
         if (admin === '0x0000000000000000000000000000000000000000') {
             console.log("\n❌ Contract NOT initialized! Admin is zero address.");
-            console.log("   The contract needs to call initialize() first.");
+            console.log("   Redeploy it with the admin passed to the constructor.");
         } else {
             console.log("\n✅ Contract is initialized");
         }
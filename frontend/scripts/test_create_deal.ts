@@ -44,20 +44,6 @@ async function main() {
         return;
     }
 
-    // Try Initialize (just to check connectivity/gas)
-    try {
-        console.log("Simulating initialize...");
-        await client.simulateContract({
-            address: CONTRACT_ADDRESS,
-            abi: ARBISECURE_ABI,
-            functionName: 'initialize',
-            account
-        });
-        console.log("Initialize simulation success (or already initialized)");
-    } catch (e) {
-        console.log("Initialize skipped/failed (likely already initialized or auth):", e.message.split('\n')[0]);
-    }
-
     try {
         console.log("Estimating gas for create_deal...");
         const gasEstimate = await client.estimateContractGas({
@@ -22,8 +22,7 @@ async function main() {
 
         if (admin === '0x0000000000000000000000000000000000000000') {
             console.log("\n⚠️  Contract NOT initialized - admin is zero address");
-            console.log("   This is expected for a fresh deployment.");
-            console.log("   The first user to call initialize() will become admin.");
+            console.log("   The admin is set by the constructor, so this deployment is unusable.");
         } else {
             console.log("\n✅ Contract IS initialized!");
             console.log(`   Admin: ${admin}`);
@@ -0,0 +1,66 @@
+#!/bin/bash
+
+# Gas and contract-size benchmarks for ArbiSecure and ArbiterRegistry
+# Usage: ./bench.sh
+#   Checks the compressed WASM size of both contracts against contracts/bench/budgets.toml.
+#   With ARBISECURE_ADDRESS and REGISTRY_ADDRESS set to deployments on the local Nitro dev
+#   node (see deploy_local.sh), also measures gas per call against the same budgets.
+#   With BASELINE_ADDRESS set to a deployment of the pre-packing revision, also compares
+#   create_deal gas against it.
+
+set -e
+
+# Resolve root directory
+SCRIPT_DIR="$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)"
+ROOT_DIR="$(dirname "$SCRIPT_DIR")"
+CONTRACTS_DIR="$ROOT_DIR/contracts"
+BUDGETS="$CONTRACTS_DIR/bench/budgets.toml"
+
+if ! command -v brotli > /dev/null; then
+    echo "❌ brotli is required to measure compressed WASM size"
+    exit 1
+fi
+
+# Reads a budget from budgets.toml, stripping digit separators
+budget() {
+    awk -F= -v key="$1" '{ gsub(/ /, "", $1) } $1 == key { gsub(/[ _]/, "", $2); print $2 }' "$BUDGETS"
+}
+
+FAILED=0
+
+# Builds a contract and compares its Brotli-compressed WASM size with its budget
+check_size() {
+    local name="$1" dir="$2" wasm="$3"
+
+    (cd "$dir" && cargo build --release --target wasm32-unknown-unknown --lib --quiet)
+
+    local size
+    size=$(brotli -c -q 11 -w 22 "$dir/target/wasm32-unknown-unknown/release/$wasm" | wc -c)
+    local limit
+    limit=$(budget "$name.wasm_size")
+
+    if [ "$size" -gt "$limit" ]; then
+        echo "❌ $name: $size bytes compressed (budget $limit)"
+        FAILED=1
+    else
+        echo "✅ $name: $size bytes compressed (budget $limit)"
+    fi
+}
+
+echo "📦 Checking compressed WASM sizes..."
+check_size arbisecure "$CONTRACTS_DIR" arbisecure_contracts.wasm
+check_size arbiter_registry "$CONTRACTS_DIR/arbiter-registry" arbiter_registry.wasm
+
+if [ -n "$ARBISECURE_ADDRESS" ] && [ -n "$REGISTRY_ADDRESS" ]; then
+    echo "⛽ Measuring gas per call..."
+    cd "$CONTRACTS_DIR"
+    cargo test --test bench -- --ignored --nocapture || FAILED=1
+else
+    echo "⚠️ ARBISECURE_ADDRESS and REGISTRY_ADDRESS not set, skipping gas benchmarks"
+fi
+
+if [ "$FAILED" -ne 0 ]; then
+    echo "❌ Budget exceeded"
+    exit 1
+fi
+echo "✅ All benchmarks within budget"
@@ -1,6 +1,6 @@
 #!/bin/bash
 
-# Deploy ArbiSecure Escrow Contract to Arbitrum Sepolia
+# Deploy ArbiSecure Escrow (implementation and proxy) to Arbitrum Sepolia
 # Usage: ./deploy.sh
 
 set -e
@@ -21,6 +21,12 @@ fi
 # Load environment variables
 source "$ROOT_DIR/.env"
 
+# The admin is set by the constructor in the deployment transaction
+if [[ -z "$ADMIN_ADDRESS" ]]; then
+    echo "❌ Error: ADMIN_ADDRESS not set in .env"
+    exit 1
+fi
+
 # Fallback to public RPC if Alchemy key is missing or not set
 if [[ -z "$ARBITRUM_SEPOLIA_RPC_URL" ]] || [[ "$ARBITRUM_SEPOLIA_RPC_URL" == *"YOUR_API_KEY"* ]]; then
     echo "⚠️  Alchemy RPC URL not configured or contains placeholder."
@@ -30,14 +36,11 @@ fi
 
 echo "Debug: Using RPC_URL: $ARBITRUM_SEPOLIA_RPC_URL"
 
-# Build the contract
-echo "📦 Building contract..."
+# Build the implementation and the proxy
+echo "📦 Building contracts..."
 cd "$ROOT_DIR/contracts"
 cargo build --release --target wasm32-unknown-unknown
-
-# Deploy using Stylus CLI
-# Deploy using Stylus CLI with Nightly and specific gas settings
-echo "🔧 Deploying contract..."
+(cd proxy && cargo build --release --target wasm32-unknown-unknown)
 
 # Check if rust-nightly is installed
 if ! rustup toolchain list | grep -q "nightly"; then
@@ -46,11 +49,47 @@ if ! rustup toolchain list | grep -q "nightly"; then
     rustup target add wasm32-unknown-unknown --toolchain nightly
 fi
 
-cargo +nightly stylus deploy \
-  --private-key=$PRIVATE_KEY \
-  --endpoint=$ARBITRUM_SEPOLIA_RPC_URL \
-  --max-fee-per-gas-gwei=10.0 \
-  --no-verify
+# Runs `cargo stylus deploy` with Nightly and specific gas settings in the current directory
+# and prints the deployed address
+deploy_contract() {
+    cargo +nightly stylus deploy \
+      --private-key=$PRIVATE_KEY \
+      --endpoint=$ARBITRUM_SEPOLIA_RPC_URL \
+      --max-fee-per-gas-gwei=10.0 \
+      --no-verify \
+      "$@" | tee /dev/stderr | grep -i "deployed code at address" | grep -oE "0x[0-9a-fA-F]{40}" | tail -1
+}
+
+# The implementation's constructor only initializes its own storage; users never call it directly
+echo "🔧 Deploying implementation..."
+IMPLEMENTATION_ADDRESS=$(deploy_contract --constructor-args=$ADMIN_ADDRESS)
+if [[ -z "$IMPLEMENTATION_ADDRESS" ]]; then
+    echo "❌ Error: could not read the implementation address from the deploy output"
+    exit 1
+fi
+
+# The proxy delegate-calls initialize(admin) on the implementation in its deployment transaction
+INITIALIZE_CALLDATA="0xc4d66de8$(printf '%064s' "${ADMIN_ADDRESS#0x}" | tr ' ' '0' | tr 'A-F' 'a-f')"
+
+echo "🔧 Deploying proxy..."
+cd "$ROOT_DIR/contracts/proxy"
+PROXY_ADDRESS=$(deploy_contract --constructor-args $IMPLEMENTATION_ADDRESS $INITIALIZE_CALLDATA)
+if [[ -z "$PROXY_ADDRESS" ]]; then
+    echo "❌ Error: could not read the proxy address from the deploy output"
+    exit 1
+fi
+
+# Point the frontend at the proxy, which holds every deal
+FRONTEND_ENV="$ROOT_DIR/frontend/.env.local"
+touch "$FRONTEND_ENV"
+if grep -q "^NEXT_PUBLIC_ESCROW_CONTRACT_ADDRESS=" "$FRONTEND_ENV"; then
+    sed -i.bak "s/^NEXT_PUBLIC_ESCROW_CONTRACT_ADDRESS=.*/NEXT_PUBLIC_ESCROW_CONTRACT_ADDRESS=$PROXY_ADDRESS/" "$FRONTEND_ENV"
+    rm -f "$FRONTEND_ENV.bak"
+else
+    echo "NEXT_PUBLIC_ESCROW_CONTRACT_ADDRESS=$PROXY_ADDRESS" >> "$FRONTEND_ENV"
+fi
 
 echo "✅ Deployment complete!"
-echo "📝 Update NEXT_PUBLIC_ESCROW_CONTRACT_ADDRESS in frontend/.env.local with the new address from output above."
+echo "   Implementation: $IMPLEMENTATION_ADDRESS"
+echo "   Proxy:          $PROXY_ADDRESS"
+echo "📝 NEXT_PUBLIC_ESCROW_CONTRACT_ADDRESS in frontend/.env.local now points at the proxy."
@@ -1,6 +1,6 @@
 #!/bin/bash
 
-# Deploy ArbiSecure Escrow Contract to Local Nitro Dev Node
+# Deploy ArbiSecure Escrow (implementation and proxy) to Local Nitro Dev Node
 # Usage: ./deploy_local.sh
 
 set -e
@@ -15,16 +15,16 @@ ROOT_DIR="$(dirname "$SCRIPT_DIR")"
 RPC_URL="http://127.0.0.1:8547"
 # Standard Nitro dev node pre-funded account
 PRIVATE_KEY="0xb6b15c8cb491557369f3c7d2c287b053eb229daa9c22138887752191c9520659"
+# Its address, set as admin by the constructor
+ADMIN_ADDRESS="0x3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E"
 
 echo "Debug: Using RPC_URL: $RPC_URL"
 
-# Build the contract
-echo "📦 Building contract..."
+# Build the implementation and the proxy
+echo "📦 Building contracts..."
 cd "$ROOT_DIR/contracts"
 cargo build --release --target wasm32-unknown-unknown
-
-# Deploy using Stylus CLI
-echo "🔧 Deploying contract..."
+(cd proxy && cargo build --release --target wasm32-unknown-unknown)
 
 # Check if rust-nightly is installed
 if ! rustup toolchain list | grep -q "nightly"; then
@@ -33,21 +33,46 @@ if ! rustup toolchain list | grep -q "nightly"; then
     rustup target add wasm32-unknown-unknown --toolchain nightly
 fi
 
-# Use nightly to avoid metadata parsing issues
-cargo +nightly stylus deploy \
-  --private-key=$PRIVATE_KEY \
-  --endpoint=$RPC_URL \
-  --no-verify \
-  --no-activate # Activation might need manual step or different flags on dev node, let's try activating by default first, but wait. run-dev-node.sh deploys a cache manager. 
-  # Actually, let's try standard deploy first. Usually --no-verify is good for local.
+# Runs `cargo stylus deploy` in the current directory and prints the deployed address.
+# Uses nightly to avoid metadata parsing issues; deployments are activated by default.
+deploy_contract() {
+    cargo +nightly stylus deploy \
+      --private-key=$PRIVATE_KEY \
+      --endpoint=$RPC_URL \
+      --no-verify \
+      "$@" | tee /dev/stderr | grep -i "deployed code at address" | grep -oE "0x[0-9a-fA-F]{40}" | tail -1
+}
+
+# The implementation's constructor only initializes its own storage; users never call it directly
+echo "🔧 Deploying implementation..."
+IMPLEMENTATION_ADDRESS=$(deploy_contract --constructor-args=$ADMIN_ADDRESS)
+if [[ -z "$IMPLEMENTATION_ADDRESS" ]]; then
+    echo "❌ Error: could not read the implementation address from the deploy output"
+    exit 1
+fi
+
+# The proxy delegate-calls initialize(admin) on the implementation in its deployment transaction
+INITIALIZE_CALLDATA="0xc4d66de8$(printf '%064s' "${ADMIN_ADDRESS#0x}" | tr ' ' '0' | tr 'A-F' 'a-f')"
 
-# Rethinking: The standard deploy command activates if not told otherwise.
-# Let's use the standard command but point to local RPC.
+echo "🔧 Deploying proxy..."
+cd "$ROOT_DIR/contracts/proxy"
+PROXY_ADDRESS=$(deploy_contract --constructor-args $IMPLEMENTATION_ADDRESS $INITIALIZE_CALLDATA)
+if [[ -z "$PROXY_ADDRESS" ]]; then
+    echo "❌ Error: could not read the proxy address from the deploy output"
+    exit 1
+fi
 
-cargo +nightly stylus deploy \
-  --private-key=$PRIVATE_KEY \
-  --endpoint=$RPC_URL \
-  --no-verify 
+# Point the frontend at the proxy, which holds every deal
+FRONTEND_ENV="$ROOT_DIR/frontend/.env.local"
+touch "$FRONTEND_ENV"
+if grep -q "^NEXT_PUBLIC_ESCROW_CONTRACT_ADDRESS=" "$FRONTEND_ENV"; then
+    sed -i.bak "s/^NEXT_PUBLIC_ESCROW_CONTRACT_ADDRESS=.*/NEXT_PUBLIC_ESCROW_CONTRACT_ADDRESS=$PROXY_ADDRESS/" "$FRONTEND_ENV"
+    rm -f "$FRONTEND_ENV.bak"
+else
+    echo "NEXT_PUBLIC_ESCROW_CONTRACT_ADDRESS=$PROXY_ADDRESS" >> "$FRONTEND_ENV"
+fi
 
 echo "✅ Local Deployment complete!"
-echo "📝 Update NEXT_PUBLIC_ESCROW_CONTRACT_ADDRESS in frontend/.env.local with the new address from output above."
+echo "   Implementation: $IMPLEMENTATION_ADDRESS"
+echo "   Proxy:          $PROXY_ADDRESS"
+echo "📝 NEXT_PUBLIC_ESCROW_CONTRACT_ADDRESS in frontend/.env.local now points at the proxy."